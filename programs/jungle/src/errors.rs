@@ -10,4 +10,88 @@ pub enum ErrorCode {
 
     #[msg("Merkle proof is invalid")]
     InvalidProof,
+
+    #[msg("The account is not a staked animal")]
+    InvalidAnimal,
+
+    #[msg("The animal is not owned by the staker")]
+    InvalidStaker,
+
+    #[msg("The remaining accounts don't match the animals")]
+    InvalidAccounts,
+
+    #[msg("The staking mode is not allowed")]
+    InvalidStakingMode,
+
+    #[msg("Invalid token metadata program")]
+    InvalidMetadataProgram,
+
+    #[msg("Invalid lock tier")]
+    InvalidLockTier,
+
+    #[msg("The animal is still locked")]
+    StillLocked,
+
+    #[msg("The emission schedule must be sorted by time, unused breakpoints last")]
+    InvalidSchedule,
+
+    #[msg("This operation is paused")]
+    Paused,
+
+    #[msg("The withdrawal is still timelocked")]
+    WithdrawalLocked,
+
+    #[msg("The withdrawal exceeds the weekly cap")]
+    WithdrawalCapExceeded,
+
+    #[msg("The withdrawal limits can only be tightened")]
+    InvalidWithdrawalLimits,
+
+    #[msg("The withdrawal exceeds the rewards not owed to stakers")]
+    InsufficientSurplus,
+
+    #[msg("The token is not part of the verified collection")]
+    InvalidCollection,
+
+    #[msg("Batch staking only supports the Merkle eligibility")]
+    InvalidEligibility,
+
+    #[msg("The attributes account doesn't match the mint")]
+    InvalidAttributes,
+
+    #[msg("An arithmetic operation overflowed")]
+    MathOverflow,
+
+    #[msg("The maximum rarity must not be null")]
+    InvalidRarity,
+
+    #[msg("The clock is earlier than the last update")]
+    ClockWentBackwards,
+
+    #[msg("The pending rewards must be paid before unstaking")]
+    RewardsNotSettled,
+
+    #[msg("Only the staker or their delegate can claim the rewards")]
+    InvalidDelegate,
+
+    #[msg("The rewards can only be sent to the staker or their recipient")]
+    InvalidRecipient,
+
+    #[msg("The animal's pending rewards don't cover the amount spent")]
+    InsufficientRewards,
+
+    #[msg("The account already has the current layout")]
+    AlreadyMigrated,
+
+    #[msg("The signer is not the owner of the jungle")]
+    InvalidOwner,
+
+    #[msg("The pause flags are unknown, or only the owner can clear them")]
+    InvalidPause,
+
+    #[msg("The animal already has the attributes of the current root")]
+    AnimalNotStale,
+
+    #[msg("The withdrawal delay must be positive")]
+    InvalidWithdrawalDelay,
 }
@@ -0,0 +1,294 @@
+use anchor_lang::prelude::*;
+
+use crate::{Eligibility, EmissionBreakpoint, Faction, LockTier, StakingMode, UnstakePolicy};
+use crate::{EMISSION_BREAKPOINTS, LOCK_TIERS};
+
+/// A jungle was created
+#[event]
+pub struct JungleInitialized {
+    pub jungle: Pubkey,
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub rewards_account: Pubkey,
+    pub maximum_rarity: u64,
+    pub maximum_rarity_multiplier: u64,
+    pub base_weekly_emissions: u64,
+    pub start: i64,
+    pub root: [u8; 32],
+    pub withdrawal_delay: i64,
+}
+
+/// The parameters of a jungle were changed
+#[event]
+pub struct JungleSet {
+    pub jungle: Pubkey,
+    pub old_maximum_rarity: u64,
+    pub new_maximum_rarity: u64,
+    pub old_maximum_rarity_multiplier: u64,
+    pub new_maximum_rarity_multiplier: u64,
+    pub old_base_weekly_emissions: u64,
+    pub new_base_weekly_emissions: u64,
+    pub old_start: i64,
+    pub new_start: i64,
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32],
+    pub old_staking_mode: StakingMode,
+    pub new_staking_mode: StakingMode,
+    pub old_lock_tiers: [LockTier; LOCK_TIERS],
+    pub new_lock_tiers: [LockTier; LOCK_TIERS],
+}
+
+/// The emission schedule of a jungle was changed
+#[event]
+pub struct EmissionScheduleSet {
+    pub jungle: Pubkey,
+    pub old_schedule: [EmissionBreakpoint; EMISSION_BREAKPOINTS],
+    pub new_schedule: [EmissionBreakpoint; EMISSION_BREAKPOINTS],
+}
+
+/// The pause flags of a jungle were changed
+#[event]
+pub struct PauseSet {
+    pub jungle: Pubkey,
+    pub old_paused: u8,
+    pub new_paused: u8,
+}
+
+/// The guardian allowed to pause a jungle was changed
+#[event]
+pub struct GuardianSet {
+    pub jungle: Pubkey,
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+/// The way tokens are checked to be part of the collection was changed
+#[event]
+pub struct EligibilitySet {
+    pub jungle: Pubkey,
+    pub old_eligibility: Eligibility,
+    pub new_eligibility: Eligibility,
+    pub old_collection: Pubkey,
+    pub new_collection: Pubkey,
+}
+
+/// The policy for rewards pending at unstake was changed
+#[event]
+pub struct UnstakePolicySet {
+    pub jungle: Pubkey,
+    pub old_unstake_policy: UnstakePolicy,
+    pub new_unstake_policy: UnstakePolicy,
+}
+
+/// The tip paid to callers of `crank_claim` was changed
+#[event]
+pub struct CrankTipSet {
+    pub jungle: Pubkey,
+    pub old_crank_tip: u64,
+    pub new_crank_tip: u64,
+}
+
+/// The limits on withdrawals from the rewards account were changed
+#[event]
+pub struct WithdrawalLimitsSet {
+    pub jungle: Pubkey,
+    pub old_delay: i64,
+    pub new_delay: i64,
+    pub old_cap: u64,
+    pub new_cap: u64,
+}
+
+/// A new owner was proposed
+#[event]
+pub struct OwnerProposed {
+    pub jungle: Pubkey,
+    pub owner: Pubkey,
+    pub old_pending_owner: Pubkey,
+    pub new_pending_owner: Pubkey,
+}
+
+/// The proposed owner accepted the ownership
+#[event]
+pub struct OwnershipAccepted {
+    pub jungle: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+/// The proposal of a new owner was cancelled
+#[event]
+pub struct OwnerProposalCancelled {
+    pub jungle: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+/// A withdrawal of surplus rewards was queued
+#[event]
+pub struct WithdrawalQueued {
+    pub jungle: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub queued_at: i64,
+}
+
+/// A queued withdrawal was executed
+#[event]
+pub struct RewardsWithdrawn {
+    pub jungle: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub ts: i64,
+}
+
+/// A queued withdrawal was cancelled
+#[event]
+pub struct WithdrawalCancelled {
+    pub jungle: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub destination: Pubkey,
+}
+
+/// The attributes of a token were written in the registry
+#[event]
+pub struct AttributesRegistered {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub rarity: u64,
+    pub faction: Faction,
+}
+
+/// An animal was staked
+#[event]
+pub struct AnimalStaked {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    pub rarity: u64,
+    pub faction: Faction,
+    pub mode: StakingMode,
+    pub lock_tier: u8,
+    pub locked_until: i64,
+    pub ts: i64,
+}
+
+/// An animal was withdrawn
+#[event]
+pub struct AnimalUnstaked {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    /// The rewards paid to the staker, none if they were forfeited
+    pub rewards: u64,
+    pub ts: i64,
+}
+
+/// A jungle was grown to the current account layout
+#[event]
+pub struct JungleMigrated {
+    pub jungle: Pubkey,
+    pub key: Pubkey,
+    pub animals_staked: u64,
+}
+
+/// An animal was moved from the legacy account layout
+#[event]
+pub struct AnimalMigrated {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    pub weight: u64,
+    pub rewards: u64,
+    pub unpaid_rewards: u64,
+}
+
+/// An animal was moved to its attributes in the current root
+#[event]
+pub struct AnimalRefreshed {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub old_rarity: u64,
+    pub new_rarity: u64,
+    pub old_faction: Faction,
+    pub new_faction: Faction,
+    /// The rewards earned at the previous rarity, paid to the staker
+    pub rewards: u64,
+    pub ts: i64,
+}
+
+/// Staked animals were moved to the weights of the current epoch
+#[event]
+pub struct AnimalsReweighted {
+    pub jungle: Pubkey,
+    pub epoch: u64,
+    pub stale_animals: u64,
+}
+
+/// The rewards of an animal were paid
+#[event]
+pub struct RewardsClaimed {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    /// The token account receiving the rewards
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// The time since the previous claim
+    pub seconds: i64,
+    pub ts: i64,
+}
+
+/// A caller of `crank_claim` was tipped
+#[event]
+pub struct CrankTipPaid {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub caller: Pubkey,
+    pub tip: u64,
+    pub ts: i64,
+}
+
+/// A staked animal was handed to another wallet
+#[event]
+pub struct StakeTransferred {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    /// The rewards paid to the previous staker
+    pub rewards: u64,
+    pub ts: i64,
+}
+
+/// A staker created their profile
+#[event]
+pub struct StakerProfileCreated {
+    pub jungle: Pubkey,
+    pub staker: Pubkey,
+}
+
+/// A staker changed who can claim for them and where
+#[event]
+pub struct ClaimDelegateSet {
+    pub jungle: Pubkey,
+    pub staker: Pubkey,
+    pub old_delegate: Pubkey,
+    pub new_delegate: Pubkey,
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+}
+
+/// Pending rewards of an animal were spent in the lottery
+#[event]
+pub struct LotteryEntered {
+    pub jungle: Pubkey,
+    pub mint: Pubkey,
+    pub staker: Pubkey,
+    pub lottery: Pubkey,
+    pub spendings: [u64; 8],
+    pub amount: u64,
+    pub ts: i64,
+}
@@ -0,0 +1,110 @@
+//! Keeps staked tokens in the staker's wallet instead of a deposit account.
+//! The escrow, approved as delegate of the staker's token account, freezes it either
+//! through the Metaplex freeze-delegated-account instructions or as the freeze authority
+//! of the mint.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{self, FreezeAccount, ThawAccount};
+
+use crate::errors::ErrorCode;
+use crate::StakingMode;
+
+/// The Metaplex token metadata program
+pub mod token_metadata {
+    solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+/// Index of the `FreezeDelegatedAccount` instruction of the token metadata program
+const FREEZE_DELEGATED_ACCOUNT: u8 = 26;
+
+/// Index of the `ThawDelegatedAccount` instruction of the token metadata program
+const THAW_DELEGATED_ACCOUNT: u8 = 27;
+
+/// The accounts needed to freeze or thaw a token staked in the staker's wallet
+pub struct FrozenToken<'info> {
+    /// The escrow, delegate of the token account
+    pub escrow: AccountInfo<'info>,
+
+    /// The staker's account holding the token
+    pub token_account: AccountInfo<'info>,
+
+    /// The mint of the token
+    pub mint: AccountInfo<'info>,
+
+    /// The master edition of the token, only used by Metaplex
+    pub edition: AccountInfo<'info>,
+
+    /// The Metaplex token metadata program
+    pub token_metadata_program: AccountInfo<'info>,
+
+    /// The program for interacting with the token
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> FrozenToken<'info> {
+    /// Freezes the token account, the escrow signing with the given seeds
+    pub fn freeze(&self, mode: StakingMode, signer: &[&[&[u8]]]) -> ProgramResult {
+        match mode {
+            StakingMode::Freeze => self.invoke_token_metadata(FREEZE_DELEGATED_ACCOUNT, signer),
+            StakingMode::FreezeAuthority => token::freeze_account(CpiContext::new_with_signer(
+                self.token_program.clone(),
+                FreezeAccount {
+                    account: self.token_account.clone(),
+                    mint: self.mint.clone(),
+                    authority: self.escrow.clone(),
+                },
+                signer,
+            )),
+            StakingMode::Deposit => Err(ErrorCode::InvalidStakingMode.into()),
+        }
+    }
+
+    /// Thaws the token account, the escrow signing with the given seeds
+    pub fn thaw(&self, mode: StakingMode, signer: &[&[&[u8]]]) -> ProgramResult {
+        match mode {
+            StakingMode::Freeze => self.invoke_token_metadata(THAW_DELEGATED_ACCOUNT, signer),
+            StakingMode::FreezeAuthority => token::thaw_account(CpiContext::new_with_signer(
+                self.token_program.clone(),
+                ThawAccount {
+                    account: self.token_account.clone(),
+                    mint: self.mint.clone(),
+                    authority: self.escrow.clone(),
+                },
+                signer,
+            )),
+            StakingMode::Deposit => Err(ErrorCode::InvalidStakingMode.into()),
+        }
+    }
+
+    fn invoke_token_metadata(&self, instruction: u8, signer: &[&[&[u8]]]) -> ProgramResult {
+        if self.token_metadata_program.key() != token_metadata::ID {
+            return Err(ErrorCode::InvalidMetadataProgram.into());
+        }
+
+        let ix = Instruction {
+            program_id: token_metadata::ID,
+            accounts: vec![
+                AccountMeta::new(self.escrow.key(), true),
+                AccountMeta::new(self.token_account.key(), false),
+                AccountMeta::new_readonly(self.edition.key(), false),
+                AccountMeta::new_readonly(self.mint.key(), false),
+                AccountMeta::new_readonly(token::ID, false),
+            ],
+            data: vec![instruction],
+        };
+        invoke_signed(
+            &ix,
+            &[
+                self.escrow.clone(),
+                self.token_account.clone(),
+                self.edition.clone(),
+                self.mint.clone(),
+                self.token_program.clone(),
+                self.token_metadata_program.clone(),
+            ],
+            signer,
+        )
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::events::OwnershipAccepted;
+use crate::Jungle;
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = pending_owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet proposed as the next owner
+    pub pending_owner: Signer<'info>,
+}
+
+/// Transfers the ownership to the proposed owner
+pub fn handler(ctx: Context<AcceptOwnership>) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    emit!(OwnershipAccepted {
+        jungle: jungle.key(),
+        old_owner: jungle.owner,
+        new_owner: jungle.pending_owner,
+    });
+
+    jungle.owner = jungle.pending_owner;
+    jungle.pending_owner = Pubkey::default();
+
+    msg!("Ownership accepted");
+
+    Ok(())
+}
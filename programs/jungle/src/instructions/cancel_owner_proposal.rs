@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::events::OwnerProposalCancelled;
+use crate::Jungle;
+
+#[derive(Accounts)]
+pub struct CancelOwnerProposal<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+}
+
+/// Clears the pending owner
+pub fn handler(ctx: Context<CancelOwnerProposal>) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    emit!(OwnerProposalCancelled {
+        jungle: jungle.key(),
+        pending_owner: jungle.pending_owner,
+    });
+
+    jungle.pending_owner = Pubkey::default();
+
+    msg!("Owner proposal cancelled");
+
+    Ok(())
+}
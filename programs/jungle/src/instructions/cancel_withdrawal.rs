@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::events::WithdrawalCancelled;
+use crate::{Jungle, Withdrawal};
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    /// The Jungle
+    #[account(
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = owner
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The queued withdrawal, closed without being executed
+    #[account(
+        mut,
+        seeds = [
+            b"withdrawal",
+            jungle.key.as_ref(),
+            withdrawal.index.to_le_bytes().as_ref()
+        ],
+        bump = withdrawal.bump,
+        close = owner
+    )]
+    pub withdrawal: Account<'info, Withdrawal>,
+
+    /// The wallet that owns the jungle
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Lets owner drop a queued withdrawal, refunding its rent
+pub fn handler(ctx: Context<CancelWithdrawal>) -> ProgramResult {
+    let withdrawal = &ctx.accounts.withdrawal;
+
+    emit!(WithdrawalCancelled {
+        jungle: ctx.accounts.jungle.key(),
+        index: withdrawal.index,
+        amount: withdrawal.amount,
+        destination: withdrawal.destination,
+    });
+
+    msg!("Withdrawal cancelled");
+
+    Ok(())
+}
@@ -1,12 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::{Jungle, Animal};
+use crate::errors::*;
+use crate::events::RewardsClaimed;
+use crate::{Jungle, Animal, StakerProfile, PAUSE_CLAIM};
 
 #[derive(Accounts)]
 pub struct ClaimStaking<'info> {
     /// The jungle state
     #[account(
+        mut,
         seeds = [
             b"jungle",
             jungle.key.as_ref()
@@ -33,27 +36,32 @@ pub struct ClaimStaking<'info> {
         mut,
         seeds = [
             b"animal".as_ref(),
+            jungle.key.as_ref(),
             animal.mint.as_ref()
         ],
         bump = animal.bumps.animal,
+        has_one = jungle,
         has_one = staker
     )]
     pub animal: Account<'info, Animal>,
 
     /// The owner of the staked token
-    #[account(mut)]
-    pub staker: Signer<'info>,
+    pub staker: AccountInfo<'info>,
+
+    /// The staker, or the delegate set in their profile
+    pub authority: Signer<'info>,
+
+    /// The profile of the staker, only used for delegated claims or to pay the recipient
+    pub profile: AccountInfo<'info>,
 
     /// The mint of the reward token
     #[account(mut)]
     pub mint: AccountInfo<'info>,
 
-    /// The user account receiving rewards
+    /// The account receiving rewards, the staker's or the recipient in their profile
     #[account(
-        mut, 
-        constraint = 
-            staker_account.owner == staker.key() &&
-            staker_account.mint == mint.key()
+        mut,
+        constraint = staker_account.mint == mint.key()
     )]
     pub staker_account: Account<'info, TokenAccount>,
 
@@ -82,15 +90,38 @@ pub struct ClaimStaking<'info> {
 
 /// Claims rewards for a staked token
 pub fn handler(ctx: Context<ClaimStaking>) -> ProgramResult {
-    let jungle = &ctx.accounts.jungle;
+    let jungle = &mut ctx.accounts.jungle;
     let animal = &mut ctx.accounts.animal;
-
-    let rarity = if animal.rarity <= jungle.maximum_rarity { animal.rarity } else { jungle.maximum_rarity };
-    let multiplier = 10000 + (jungle.maximum_rarity_multiplier - 10000) * rarity / jungle.maximum_rarity;
-    let seconds_elapsed = ctx.accounts.clock.unix_timestamp - animal.last_claim;
-    let weekly_emissions = jungle.base_weekly_emissions * multiplier / 10000;
-    let rewards_amount = weekly_emissions * (seconds_elapsed as u64) / 604800;
-    
+    if jungle.is_paused(PAUSE_CLAIM) {
+        return Err(ErrorCode::Paused.into());
+    }
+
+    let profile = StakerProfile::load(
+        &ctx.accounts.profile,
+        &jungle.key,
+        &ctx.accounts.staker.key(),
+        ctx.program_id,
+    )?;
+    StakerProfile::authorize_claim(
+        profile.as_ref(),
+        &ctx.accounts.staker.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.staker_account.key(),
+        &ctx.accounts.staker_account.owner,
+    )?;
+
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+    let rewards_amount = jungle.settle(animal)?;
+
+    emit!(RewardsClaimed {
+        jungle: jungle.key(),
+        mint: animal.mint,
+        staker: animal.staker,
+        destination: ctx.accounts.staker_account.key(),
+        amount: rewards_amount,
+        seconds: ctx.accounts.clock.unix_timestamp - animal.last_claim,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
     animal.last_claim = ctx.accounts.clock.unix_timestamp;
 
     let seeds = &[
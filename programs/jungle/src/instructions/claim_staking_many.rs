@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::RewardsClaimed;
+use crate::{Animal, Jungle, StakerProfile, PAUSE_CLAIM};
+
+#[derive(Accounts)]
+pub struct ClaimStakingMany<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = mint,
+        has_one = rewards_account
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The owner of the staked tokens
+    pub staker: AccountInfo<'info>,
+
+    /// The staker, or the delegate set in their profile
+    pub authority: Signer<'info>,
+
+    /// The profile of the staker, only used for delegated claims or to pay the recipient
+    pub profile: AccountInfo<'info>,
+
+    /// The mint of the reward token
+    #[account(mut)]
+    pub mint: AccountInfo<'info>,
+
+    /// The account receiving rewards, the staker's or the recipient in their profile
+    #[account(
+        mut,
+        constraint = staker_account.mint == mint.key()
+    )]
+    pub staker_account: Account<'info, TokenAccount>,
+
+    /// The account holding the staking rewards
+    #[account(
+        mut,
+        seeds = [
+            b"rewards",
+            jungle.key.as_ref(),
+            jungle.mint.as_ref()
+        ],
+        bump = jungle.bumps.rewards,
+    )]
+    pub rewards_account: Account<'info, TokenAccount>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Claims rewards for all the staked tokens passed as remaining accounts
+pub fn handler(ctx: Context<ClaimStakingMany>) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    if jungle.is_paused(PAUSE_CLAIM) {
+        return Err(ErrorCode::Paused.into());
+    }
+
+    let profile = StakerProfile::load(
+        &ctx.accounts.profile,
+        &jungle.key,
+        &ctx.accounts.staker.key(),
+        ctx.program_id,
+    )?;
+    StakerProfile::authorize_claim(
+        profile.as_ref(),
+        &ctx.accounts.staker.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.staker_account.key(),
+        &ctx.accounts.staker_account.owner,
+    )?;
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+
+    let mut rewards_amount: u64 = 0;
+    for info in ctx.remaining_accounts.iter() {
+        let mut animal: Account<Animal> = Account::try_from(info)?;
+
+        let address = Pubkey::create_program_address(
+            &[b"animal", jungle.key.as_ref(), animal.mint.as_ref(), &[animal.bumps.animal]],
+            ctx.program_id,
+        )?;
+        if address != info.key() {
+            return Err(ErrorCode::InvalidAnimal.into());
+        }
+        if animal.staker != ctx.accounts.staker.key() {
+            return Err(ErrorCode::InvalidStaker.into());
+        }
+
+        let animal_rewards = jungle.settle(&mut animal)?;
+        rewards_amount = rewards_amount
+            .checked_add(animal_rewards)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(RewardsClaimed {
+            jungle: jungle.key(),
+            mint: animal.mint,
+            staker: animal.staker,
+            destination: ctx.accounts.staker_account.key(),
+            amount: animal_rewards,
+            seconds: ctx.accounts.clock.unix_timestamp - animal.last_claim,
+            ts: ctx.accounts.clock.unix_timestamp,
+        });
+        animal.last_claim = ctx.accounts.clock.unix_timestamp;
+
+        // Persist right away so an animal passed twice is only paid once
+        animal.exit(ctx.program_id)?;
+    }
+
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle.key.as_ref(),
+        &[jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    let context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.rewards_account.to_account_info(),
+            to: ctx.accounts.staker_account.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(context, rewards_amount)?;
+
+    msg!("Rewards claimed");
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{self, AssociatedToken, Create};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::{CrankTipPaid, RewardsClaimed};
+use crate::{to_u64, Animal, Jungle, MAX_CRANK_TIP_BPS, PAUSE_CLAIM, PAUSE_WITHDRAW, WEEK};
+
+#[derive(Accounts)]
+pub struct CrankClaim<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = mint,
+        has_one = rewards_account
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The staking account
+    #[account(
+        mut,
+        seeds = [
+            b"animal".as_ref(),
+            jungle.key.as_ref(),
+            animal.mint.as_ref()
+        ],
+        bump = animal.bumps.animal,
+        has_one = jungle,
+        has_one = staker
+    )]
+    pub animal: Account<'info, Animal>,
+
+    /// The owner of the staked token
+    pub staker: AccountInfo<'info>,
+
+    /// The associated token account of the staker, created if needed
+    #[account(
+        mut,
+        address = associated_token::get_associated_token_address(staker.key, mint.key)
+    )]
+    pub staker_account: AccountInfo<'info>,
+
+    /// The mint of the reward token
+    pub mint: AccountInfo<'info>,
+
+    /// The account holding the staking rewards
+    #[account(mut)]
+    pub rewards_account: Account<'info, TokenAccount>,
+
+    /// Whoever calls the crank, paying for the staker's account if it is created
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// The caller's account receiving the tip, only used if a tip is paid
+    #[account(mut)]
+    pub caller_account: AccountInfo<'info>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// The program creating associated token accounts
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CrankClaim<'info> {
+    fn transfer_context(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.rewards_account.to_account_info(),
+                to,
+                authority: self.escrow.to_account_info(),
+            },
+        )
+    }
+}
+
+/// Claims the rewards of an animal for its staker, tipping the caller if it was unclaimed for a week
+pub fn handler(ctx: Context<CrankClaim>) -> ProgramResult {
+    let now = ctx.accounts.clock.unix_timestamp;
+    let jungle = &mut ctx.accounts.jungle;
+    let animal = &mut ctx.accounts.animal;
+    if jungle.is_paused(PAUSE_CLAIM) {
+        return Err(ErrorCode::Paused.into());
+    }
+
+    jungle.update_rewards(now)?;
+    let rewards_amount = jungle.settle(animal)?;
+
+    // The tip comes from the surplus, and cranking the same animal again earns nothing for a week
+    // Like withdrawals, tips are limited by the weekly cap and can't drain the surplus at once
+    let unclaimed_for_week = now >= animal.last_claim.saturating_add(WEEK);
+    let remaining = ctx.accounts.rewards_account.amount.saturating_sub(rewards_amount);
+    let tip = if rewards_amount > 0 && unclaimed_for_week && !jungle.is_paused(PAUSE_WITHDRAW) {
+        let max_tip = rewards_amount as u128 * MAX_CRANK_TIP_BPS as u128 / 10000;
+        jungle
+            .crank_tip
+            .min(to_u64(max_tip)?)
+            .min(jungle.surplus(remaining))
+            .min(jungle.withdrawal_allowance(now))
+    } else {
+        0
+    };
+    if tip > 0 {
+        jungle.record_withdrawal(now, tip)?;
+    }
+
+    emit!(RewardsClaimed {
+        jungle: jungle.key(),
+        mint: animal.mint,
+        staker: animal.staker,
+        destination: ctx.accounts.staker_account.key(),
+        amount: rewards_amount,
+        seconds: now - animal.last_claim,
+        ts: now,
+    });
+    animal.last_claim = now;
+    let jungle_key = jungle.key;
+    let escrow_bump = jungle.bumps.escrow;
+
+    if ctx.accounts.staker_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.caller.to_account_info(),
+                associated_token: ctx.accounts.staker_account.clone(),
+                authority: ctx.accounts.staker.clone(),
+                mint: ctx.accounts.mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+        ))?;
+    }
+
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle_key.as_ref(),
+        &[escrow_bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let staker_account = ctx.accounts.staker_account.clone();
+    token::transfer(
+        ctx.accounts.transfer_context(staker_account).with_signer(signer),
+        rewards_amount,
+    )?;
+
+    if tip > 0 {
+        let caller_account: Account<TokenAccount> =
+            Account::try_from(&ctx.accounts.caller_account)?;
+        if caller_account.mint != ctx.accounts.mint.key() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+        let caller_account = ctx.accounts.caller_account.clone();
+        token::transfer(
+            ctx.accounts.transfer_context(caller_account).with_signer(signer),
+            tip,
+        )?;
+
+        emit!(CrankTipPaid {
+            jungle: ctx.accounts.jungle.key(),
+            mint: ctx.accounts.animal.mint,
+            caller: ctx.accounts.caller.key(),
+            tip,
+            ts: now,
+        });
+    }
+
+    msg!("Rewards cranked");
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use lottery::program::Lottery;
+
+use crate::errors::*;
+use crate::events::LotteryEntered;
+use crate::{Animal, Jungle, PAUSE_CLAIM};
+
+#[derive(Accounts)]
+pub struct EnterLottery<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = rewards_account
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The staking account whose rewards are spent
+    #[account(
+        mut,
+        seeds = [
+            b"animal".as_ref(),
+            jungle.key.as_ref(),
+            animal.mint.as_ref()
+        ],
+        bump = animal.bumps.animal,
+        has_one = jungle,
+        has_one = staker
+    )]
+    pub animal: Account<'info, Animal>,
+
+    /// The owner of the animal, playing the lottery
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// The account holding the staking rewards, spent directly
+    #[account(mut)]
+    pub rewards_account: Account<'info, TokenAccount>,
+
+    /// The lottery
+    pub lottery: AccountInfo<'info>,
+
+    /// The current lottery round
+    #[account(mut)]
+    pub lottery_round: AccountInfo<'info>,
+
+    /// The participation of the staker, created if it doesn't exist yet
+    #[account(mut)]
+    pub participation: AccountInfo<'info>,
+
+    /// The account of the lottery receiving spent tokens
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    /// The lottery program
+    pub lottery_program: Program<'info, Lottery>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Spends the pending rewards of an animal in the lottery without paying them to the staker
+pub fn handler(ctx: Context<EnterLottery>, bump: u8, spendings: [u64; 8]) -> ProgramResult {
+    let now = ctx.accounts.clock.unix_timestamp;
+    let jungle = &mut ctx.accounts.jungle;
+    if jungle.is_paused(PAUSE_CLAIM) {
+        return Err(ErrorCode::Paused.into());
+    }
+
+    let amount = spendings
+        .iter()
+        .try_fold(0u64, |sum, spending| sum.checked_add(*spending))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    jungle.update_rewards(now)?;
+    jungle.spend(&mut ctx.accounts.animal, amount)?;
+    ctx.accounts.animal.last_claim = now;
+
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle.key.as_ref(),
+        &[jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    // The escrow spends from the rewards account on behalf of the staker
+    let program = ctx.accounts.lottery_program.to_account_info();
+    if ctx.accounts.participation.data_is_empty() {
+        let accounts = lottery::cpi::accounts::ParticipateFor {
+            lottery: ctx.accounts.lottery.clone(),
+            lottery_round: ctx.accounts.lottery_round.clone(),
+            participation: ctx.accounts.participation.clone(),
+            player: ctx.accounts.staker.to_account_info(),
+            authority: ctx.accounts.escrow.clone(),
+            player_account: ctx.accounts.rewards_account.to_account_info(),
+            treasury: ctx.accounts.treasury.clone(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            clock: ctx.accounts.clock.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        lottery::cpi::participate_for(
+            CpiContext::new_with_signer(program, accounts, signer),
+            bump,
+            spendings,
+        )?;
+    } else {
+        let accounts = lottery::cpi::accounts::UpdateParticipationFor {
+            lottery: ctx.accounts.lottery.clone(),
+            lottery_round: ctx.accounts.lottery_round.clone(),
+            participation: ctx.accounts.participation.clone(),
+            player: ctx.accounts.staker.to_account_info(),
+            authority: ctx.accounts.escrow.clone(),
+            player_account: ctx.accounts.rewards_account.to_account_info(),
+            treasury: ctx.accounts.treasury.clone(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            clock: ctx.accounts.clock.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        lottery::cpi::update_participation_for(
+            CpiContext::new_with_signer(program, accounts, signer),
+            spendings,
+        )?;
+    }
+
+    emit!(LotteryEntered {
+        jungle: ctx.accounts.jungle.key(),
+        mint: ctx.accounts.animal.mint,
+        staker: ctx.accounts.staker.key(),
+        lottery: ctx.accounts.lottery.key(),
+        spendings,
+        amount,
+        ts: now,
+    });
+
+    msg!("Lottery entered");
+
+    Ok(())
+}
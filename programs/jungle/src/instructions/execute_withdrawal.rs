@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::RewardsWithdrawn;
+use crate::{Jungle, Withdrawal, PAUSE_WITHDRAW};
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = rewards_account,
+        has_one = owner
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The account holding the staking rewards
+    #[account(mut)]
+    pub rewards_account: Account<'info, TokenAccount>,
+
+    /// The queued withdrawal, closed once executed
+    #[account(
+        mut,
+        seeds = [
+            b"withdrawal",
+            jungle.key.as_ref(),
+            withdrawal.index.to_le_bytes().as_ref()
+        ],
+        bump = withdrawal.bump,
+        has_one = destination,
+        close = owner
+    )]
+    pub withdrawal: Account<'info, Withdrawal>,
+
+    /// The wallet that owns the jungle
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The account receiving the rewards
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+
+    /// The program for interacting with the token.
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Lets owner withdraw the queued rewards from the escrow once the delay has passed
+pub fn handler(ctx: Context<ExecuteWithdrawal>) -> ProgramResult {
+    let now = ctx.accounts.clock.unix_timestamp;
+    let amount = ctx.accounts.withdrawal.amount;
+    let jungle = &mut ctx.accounts.jungle;
+    if jungle.is_paused(PAUSE_WITHDRAW) {
+        return Err(ErrorCode::Paused.into());
+    }
+    // The delay in force now applies, even if it grew since the withdrawal was queued
+    let unlock_time = ctx
+        .accounts
+        .withdrawal
+        .queued_at
+        .checked_add(jungle.withdrawal_delay)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if now < unlock_time {
+        return Err(ErrorCode::WithdrawalLocked.into());
+    }
+
+    // Only the rewards not owed to stakers can be withdrawn
+    jungle.update_rewards(now)?;
+    if amount > jungle.surplus(ctx.accounts.rewards_account.amount) {
+        return Err(ErrorCode::InsufficientSurplus.into());
+    }
+
+    // Limit how much can leave the vault each week
+    jungle.record_withdrawal(now, amount)?;
+
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle.key.as_ref(),
+        &[jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.rewards_account.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    emit!(RewardsWithdrawn {
+        jungle: jungle.key(),
+        index: ctx.accounts.withdrawal.index,
+        amount,
+        destination: ctx.accounts.destination.key(),
+        ts: now,
+    });
+
+    msg!("Rewards withdrawn");
+
+    Ok(())
+}
@@ -2,7 +2,8 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
 
 use crate::errors::*;
-use crate::{Jungle, InitializeJungleBumps};
+use crate::events::JungleInitialized;
+use crate::{Jungle, InitializeJungleBumps, LockTier};
 
 #[derive(Accounts)]
 #[instruction(bumps: InitializeJungleBumps)]
@@ -64,6 +65,7 @@ pub struct InitializeJungle<'info> {
 }
 
 /// Initializes the jungle parameters
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<InitializeJungle>,
     bumps: InitializeJungleBumps,
@@ -72,11 +74,18 @@ pub fn handler(
     base_weekly_emissions: u64,
     start: i64,
     root: [u8; 32],
+    withdrawal_delay: i64,
 ) -> ProgramResult {
     msg!("Init Jungle");
     if max_multiplier < 10000 {
         return Err(ErrorCode::InvalidMultiplier.into());
     }
+    if max_rarity == 0 {
+        return Err(ErrorCode::InvalidRarity.into());
+    }
+    if withdrawal_delay <= 0 {
+        return Err(ErrorCode::InvalidWithdrawalDelay.into());
+    }
 
     let jungle = &mut ctx.accounts.jungle;
     jungle.key = ctx.accounts.jungle_key.key();
@@ -90,6 +99,26 @@ pub fn handler(
     jungle.base_weekly_emissions = base_weekly_emissions;
     jungle.start = start;
     jungle.root = root;
+    jungle.last_update = start;
+    jungle.withdrawal_delay = withdrawal_delay;
+    jungle.lock_tiers[0] = LockTier {
+        duration: 0,
+        multiplier: 10000,
+    };
+
+    emit!(JungleInitialized {
+        jungle: jungle.key(),
+        key: jungle.key,
+        owner: jungle.owner,
+        mint: jungle.mint,
+        rewards_account: jungle.rewards_account,
+        maximum_rarity: max_rarity,
+        maximum_rarity_multiplier: max_multiplier,
+        base_weekly_emissions,
+        start,
+        root,
+        withdrawal_delay,
+    });
 
     msg!("Jungle initialized");
 
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::events::StakerProfileCreated;
+use crate::{Jungle, StakerProfile};
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct InitStakerProfile<'info> {
+    /// The Jungle
+    #[account(
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The created profile
+    #[account(
+        init,
+        payer = staker,
+        seeds = [
+            b"profile",
+            jungle.key.as_ref(),
+            staker.key().as_ref()
+        ],
+        bump = bump,
+    )]
+    pub profile: Account<'info, StakerProfile>,
+
+    /// The owner of the animals
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates an empty profile for the staker
+pub fn handler(ctx: Context<InitStakerProfile>, bump: u8) -> ProgramResult {
+    let profile = &mut ctx.accounts.profile;
+    profile.bump = bump;
+    profile.jungle = ctx.accounts.jungle.key;
+    profile.staker = ctx.accounts.staker.key();
+
+    emit!(StakerProfileCreated {
+        jungle: ctx.accounts.jungle.key(),
+        staker: profile.staker,
+    });
+
+    msg!("Staker profile created");
+
+    Ok(())
+}
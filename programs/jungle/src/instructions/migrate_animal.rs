@@ -0,0 +1,302 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, CloseAccount, InitializeAccount, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::AnimalMigrated;
+use crate::{
+    create_pda, to_u64, Animal, Faction, Jungle, LockTier, StakeAnimalBumps, StakingMode, PAUSE_CLAIM,
+    WEEK,
+};
+
+#[derive(Accounts)]
+#[instruction(bumps: StakeAnimalBumps)]
+pub struct MigrateAnimal<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = rewards_account
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The staking account derived from the mint only, closed by the migration
+    #[account(mut, owner = *program_id)]
+    pub legacy_animal: AccountInfo<'info>,
+
+    /// The staking account derived from the jungle and the mint
+    #[account(
+        init,
+        payer = staker,
+        seeds = [
+            b"animal",
+            jungle.key.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump = bumps.animal,
+    )]
+    pub animal: Account<'info, Animal>,
+
+    /// The owner of the animal
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// The mint of the staked token
+    pub mint: AccountInfo<'info>,
+
+    /// The account holding the token derived from the mint only
+    #[account(mut)]
+    pub legacy_deposit_account: AccountInfo<'info>,
+
+    /// The account that will hold the token
+    #[account(mut)]
+    pub deposit_account: AccountInfo<'info>,
+
+    /// The account paying staking rewards
+    #[account(mut)]
+    pub rewards_account: Account<'info, TokenAccount>,
+
+    /// The staker's account receiving the rewards earned before the migration
+    #[account(
+        mut,
+        constraint = staker_rewards_account.mint == jungle.mint
+    )]
+    pub staker_rewards_account: Account<'info, TokenAccount>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The staking account of an animal staked before the accounts were namespaced by jungle
+#[derive(AnchorDeserialize)]
+struct LegacyAnimal {
+    bumps: StakeAnimalBumps,
+    mint: Pubkey,
+    staker: Pubkey,
+    rarity: u64,
+    faction: u8,
+    last_claim: i64,
+}
+
+impl LegacyAnimal {
+    /// The rewards earned since the last claim, at the legacy rate of the animal's rarity
+    fn rewards(&self, jungle: &Jungle, now: i64) -> std::result::Result<u64, ProgramError> {
+        let multiplier = jungle.weight(
+            self.rarity,
+            &LockTier {
+                duration: 0,
+                multiplier: 10000,
+            },
+        )?;
+        let elapsed = if now > self.last_claim { (now - self.last_claim) as u128 } else { 0 };
+        let rewards = (jungle.base_weekly_emissions as u128 * multiplier as u128 / 10000)
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::MathOverflow)?
+            / WEEK as u128;
+        to_u64(rewards)
+    }
+}
+
+impl<'info> MigrateAnimal<'info> {
+    /// Reads the legacy animal, whose token is always deposited
+    fn legacy_animal(&self) -> std::result::Result<LegacyAnimal, ProgramError> {
+        let data = self.legacy_animal.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != Animal::discriminator() {
+            return Err(ErrorCode::InvalidAnimal.into());
+        }
+        Ok(LegacyAnimal::deserialize(&mut &data[8..])?)
+    }
+
+    /// Creates the new deposit account, owned by the escrow
+    fn create_deposit(&self, program_id: &Pubkey, bump: u8) -> ProgramResult {
+        let jungle_key = self.jungle.key;
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[b"deposit", jungle_key.as_ref(), mint_key.as_ref(), &[bump]];
+        let address = Pubkey::create_program_address(seeds, program_id)?;
+        if address != self.deposit_account.key() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        create_pda(
+            &self.staker.to_account_info(),
+            &self.deposit_account,
+            &self.system_program.to_account_info(),
+            &self.rent,
+            seeds,
+            TokenAccount::LEN,
+            &token::ID,
+        )?;
+        token::initialize_account(CpiContext::new(
+            self.token_program.to_account_info(),
+            InitializeAccount {
+                account: self.deposit_account.clone(),
+                mint: self.mint.clone(),
+                authority: self.escrow.clone(),
+                rent: self.rent.to_account_info(),
+            },
+        ))
+    }
+}
+
+/// Moves an animal staked before the accounts were namespaced by jungle
+/// The rewards it earned at the legacy rate are paid, then it earns from the accumulator
+pub fn handler(ctx: Context<MigrateAnimal>, bumps: StakeAnimalBumps) -> ProgramResult {
+    let legacy = ctx.accounts.legacy_animal()?;
+    let mint_key = ctx.accounts.mint.key();
+
+    let legacy_address = Pubkey::create_program_address(
+        &[b"animal", mint_key.as_ref(), &[legacy.bumps.animal]],
+        ctx.program_id,
+    )?;
+    if legacy_address != ctx.accounts.legacy_animal.key() || legacy.mint != mint_key {
+        return Err(ErrorCode::InvalidAnimal.into());
+    }
+    if legacy.staker != ctx.accounts.staker.key() {
+        return Err(ErrorCode::InvalidStaker.into());
+    }
+    let faction = Faction::try_from_slice(&[legacy.faction]).map_err(|_| ErrorCode::InvalidAnimal)?;
+
+    let jungle_key = ctx.accounts.jungle.key;
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle_key.as_ref(),
+        &[ctx.accounts.jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Legacy animals don't record their jungle, but their token is held by its escrow
+    let legacy_deposit_address = Pubkey::create_program_address(
+        &[b"deposit", mint_key.as_ref(), &[legacy.bumps.deposit]],
+        ctx.program_id,
+    )?;
+    if legacy_deposit_address != ctx.accounts.legacy_deposit_account.key() {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+    let legacy_deposit: Account<TokenAccount> =
+        Account::try_from(&ctx.accounts.legacy_deposit_account)?;
+    if legacy_deposit.owner != ctx.accounts.jungle.escrow {
+        return Err(ErrorCode::InvalidAnimal.into());
+    }
+
+    ctx.accounts.create_deposit(ctx.program_id, bumps.deposit)?;
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.legacy_deposit_account.clone(),
+                to: ctx.accounts.deposit_account.clone(),
+                authority: ctx.accounts.escrow.clone(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.legacy_deposit_account.clone(),
+            destination: ctx.accounts.staker.to_account_info(),
+            authority: ctx.accounts.escrow.clone(),
+        },
+        signer,
+    ))?;
+
+    // The legacy rewards were never counted in the liabilities, so they can only be paid from the surplus
+    // What can't be paid now, including while claims are paused, stays owed on the animal
+    let now = ctx.accounts.clock.unix_timestamp;
+    let jungle = &mut ctx.accounts.jungle;
+    jungle.update_rewards(now)?;
+    let legacy_rewards = legacy.rewards(jungle, now)?;
+    let rewards_amount = if jungle.is_paused(PAUSE_CLAIM) {
+        0
+    } else {
+        legacy_rewards.min(jungle.surplus(ctx.accounts.rewards_account.amount))
+    };
+    let unpaid_rewards = legacy_rewards - rewards_amount;
+    if rewards_amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.rewards_account.to_account_info(),
+                    to: ctx.accounts.staker_rewards_account.to_account_info(),
+                    authority: ctx.accounts.escrow.clone(),
+                },
+                signer,
+            ),
+            rewards_amount,
+        )?;
+    }
+
+    let animal = &mut ctx.accounts.animal;
+    **animal = Animal {
+        bumps,
+        mint: legacy.mint,
+        staker: legacy.staker,
+        rarity: legacy.rarity,
+        faction,
+        last_claim: now,
+        weight: jungle.weight(legacy.rarity, &jungle.lock_tiers[0])?,
+        mode: StakingMode::Deposit,
+        epoch: jungle.epoch,
+        jungle: jungle.key(),
+        // The attributes were proven against the root the jungle was initialized with
+        root_version: 0,
+        unpaid_rewards,
+        ..Default::default()
+    };
+
+    // Legacy animals are already counted as staked, but not in the weights
+    jungle.total_weight = jungle
+        .total_weight
+        .checked_add(animal.weight)
+        .ok_or(ErrorCode::MathOverflow)?;
+    jungle.add_to_faction(animal)?;
+    animal.reward_debt = animal.accrued_rewards(jungle)?;
+    jungle.liabilities = jungle
+        .liabilities
+        .checked_add(unpaid_rewards)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Close the legacy animal
+    let legacy_info = &ctx.accounts.legacy_animal;
+    let staker_info = ctx.accounts.staker.to_account_info();
+    **staker_info.lamports.borrow_mut() += legacy_info.lamports();
+    **legacy_info.lamports.borrow_mut() = 0;
+    legacy_info.try_borrow_mut_data()?.fill(0);
+
+    emit!(AnimalMigrated {
+        jungle: animal.jungle,
+        mint: animal.mint,
+        staker: animal.staker,
+        weight: animal.weight,
+        rewards: rewards_amount,
+        unpaid_rewards,
+    });
+
+    msg!("Animal migrated");
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::Discriminator;
+
+use crate::errors::*;
+use crate::events::JungleMigrated;
+use crate::{InitializeJungleBumps, Jungle, LockTier, DEFAULT_WITHDRAWAL_DELAY};
+
+#[derive(Accounts)]
+pub struct MigrateJungle<'info> {
+    /// The Jungle, still in the layout it was initialized with
+    #[account(mut, owner = *program_id)]
+    pub jungle: AccountInfo<'info>,
+
+    /// The wallet owning the jungle, paying for the larger account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The Jungle as initialized before the rewards accumulator
+#[derive(AnchorDeserialize)]
+struct LegacyJungle {
+    key: Pubkey,
+    owner: Pubkey,
+    bumps: InitializeJungleBumps,
+    escrow: Pubkey,
+    mint: Pubkey,
+    rewards_account: Pubkey,
+    animals_staked: u64,
+    maximum_rarity: u64,
+    maximum_rarity_multiplier: u64,
+    base_weekly_emissions: u64,
+    start: i64,
+    root: [u8; 32],
+}
+
+/// Grows a Jungle initialized before the rewards accumulator to the current layout
+/// Its legacy animals stay counted as staked, and join the weights when they are migrated
+pub fn handler(ctx: Context<MigrateJungle>) -> ProgramResult {
+    let info = &ctx.accounts.jungle;
+    let space = 8 + Jungle::default().try_to_vec()?.len();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != Jungle::discriminator() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+        if data.len() >= space {
+            return Err(ErrorCode::AlreadyMigrated.into());
+        }
+        LegacyJungle::deserialize(&mut &data[8..])?
+    };
+
+    let address = Pubkey::create_program_address(
+        &[b"jungle", legacy.key.as_ref(), &[legacy.bumps.jungle]],
+        ctx.program_id,
+    )?;
+    if address != info.key() {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+    if legacy.owner != ctx.accounts.owner.key() {
+        return Err(ErrorCode::InvalidOwner.into());
+    }
+
+    let required = ctx.accounts.rent.minimum_balance(space);
+    if info.lamports() < required {
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.owner.key(),
+                &address,
+                required - info.lamports(),
+            ),
+            &[
+                ctx.accounts.owner.to_account_info(),
+                info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    info.realloc(space, true)?;
+
+    // The accumulator starts now, the legacy animals having earned at the legacy rate until then
+    let now = ctx.accounts.clock.unix_timestamp;
+    let mut jungle = Jungle {
+        key: legacy.key,
+        owner: legacy.owner,
+        bumps: legacy.bumps,
+        escrow: legacy.escrow,
+        mint: legacy.mint,
+        rewards_account: legacy.rewards_account,
+        animals_staked: legacy.animals_staked,
+        maximum_rarity: legacy.maximum_rarity,
+        maximum_rarity_multiplier: legacy.maximum_rarity_multiplier,
+        base_weekly_emissions: legacy.base_weekly_emissions,
+        start: legacy.start,
+        root: legacy.root,
+        last_update: if now > legacy.start { now } else { legacy.start },
+        withdrawal_delay: DEFAULT_WITHDRAWAL_DELAY,
+        ..Default::default()
+    };
+    jungle.lock_tiers[0] = LockTier {
+        duration: 0,
+        multiplier: 10000,
+    };
+    let mut data: &mut [u8] = &mut info.try_borrow_mut_data()?;
+    jungle.try_serialize(&mut data)?;
+
+    emit!(JungleMigrated {
+        jungle: address,
+        key: jungle.key,
+        animals_staked: jungle.animals_staked,
+    });
+
+    msg!("Jungle migrated");
+
+    Ok(())
+}
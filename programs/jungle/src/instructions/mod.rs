@@ -1,13 +1,65 @@
 pub mod init_jungle;
 pub mod set_jungle;
-pub mod withdraw_rewards;
+pub mod set_emission_schedule;
+pub mod set_eligibility;
+pub mod set_unstake_policy;
+pub mod propose_owner;
+pub mod accept_ownership;
+pub mod cancel_owner_proposal;
+pub mod set_guardian;
+pub mod set_pause;
+pub mod set_withdrawal_limits;
+pub mod register_attributes;
+pub mod queue_withdrawal;
+pub mod execute_withdrawal;
+pub mod cancel_withdrawal;
 pub mod stake_animal;
+pub mod stake_animal_in_wallet;
 pub mod unstake_animal;
+pub mod refresh_animal;
+pub mod transfer_stake;
+pub mod stake_animals;
+pub mod unstake_animals;
+pub mod migrate_animal;
+pub mod migrate_jungle;
+pub mod init_staker_profile;
+pub mod set_claim_delegate;
 pub mod claim_staking;
+pub mod claim_staking_many;
+pub mod reweight_animals;
+pub mod set_crank_tip;
+pub mod crank_claim;
+pub mod enter_lottery;
 
 pub use init_jungle::*;
 pub use set_jungle::*;
-pub use withdraw_rewards::*;
+pub use set_emission_schedule::*;
+pub use set_eligibility::*;
+pub use set_unstake_policy::*;
+pub use propose_owner::*;
+pub use accept_ownership::*;
+pub use cancel_owner_proposal::*;
+pub use set_guardian::*;
+pub use set_pause::*;
+pub use set_withdrawal_limits::*;
+pub use register_attributes::*;
+pub use queue_withdrawal::*;
+pub use execute_withdrawal::*;
+pub use cancel_withdrawal::*;
 pub use stake_animal::*;
+pub use stake_animal_in_wallet::*;
 pub use unstake_animal::*;
-pub use claim_staking::*;
\ No newline at end of file
+pub use refresh_animal::*;
+pub use transfer_stake::*;
+pub use stake_animals::*;
+pub use unstake_animals::*;
+pub use migrate_animal::*;
+pub use migrate_jungle::*;
+pub use init_staker_profile::*;
+pub use set_claim_delegate::*;
+pub use claim_staking::*;
+pub use claim_staking_many::*;
+pub use reweight_animals::*;
+pub use set_crank_tip::*;
+pub use crank_claim::*;
+pub use enter_lottery::*;
\ No newline at end of file
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::events::OwnerProposed;
+use crate::Jungle;
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+
+    /// The wallet proposed as the next owner
+    pub new_owner: AccountInfo<'info>,
+}
+
+/// Proposes a new owner, replacing any pending proposal
+pub fn handler(ctx: Context<ProposeOwner>) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    emit!(OwnerProposed {
+        jungle: jungle.key(),
+        owner: jungle.owner,
+        old_pending_owner: jungle.pending_owner,
+        new_pending_owner: ctx.accounts.new_owner.key(),
+    });
+
+    jungle.pending_owner = ctx.accounts.new_owner.key();
+
+    msg!("Owner proposed");
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::*;
+use crate::events::WithdrawalQueued;
+use crate::{Jungle, Withdrawal};
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct QueueWithdrawal<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = owner
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The queued withdrawal
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            b"withdrawal",
+            jungle.key.as_ref(),
+            jungle.withdrawals_queued.to_le_bytes().as_ref()
+        ],
+        bump = bump,
+    )]
+    pub withdrawal: Account<'info, Withdrawal>,
+
+    /// The wallet that owns the jungle
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The account that will receive the rewards
+    #[account(constraint = destination.mint == jungle.mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Records a withdrawal that can be executed once the jungle's delay has passed
+pub fn handler(ctx: Context<QueueWithdrawal>, bump: u8, amount: u64) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    if jungle.withdrawal_cap != 0 && amount > jungle.withdrawal_cap {
+        return Err(ErrorCode::WithdrawalCapExceeded.into());
+    }
+
+    let withdrawal = &mut ctx.accounts.withdrawal;
+    withdrawal.bump = bump;
+    withdrawal.index = jungle.withdrawals_queued;
+    withdrawal.amount = amount;
+    withdrawal.destination = ctx.accounts.destination.key();
+    withdrawal.queued_at = ctx.accounts.clock.unix_timestamp;
+
+    jungle.withdrawals_queued += 1;
+
+    emit!(WithdrawalQueued {
+        jungle: jungle.key(),
+        index: withdrawal.index,
+        amount,
+        destination: withdrawal.destination,
+        queued_at: withdrawal.queued_at,
+    });
+
+    msg!("Withdrawal queued");
+
+    Ok(())
+}
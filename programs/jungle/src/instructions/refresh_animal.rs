@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::AnimalRefreshed;
+use crate::merkle_proof;
+use crate::{Animal, AnimalAttributes, Faction, Jungle, PAUSE_CLAIM};
+
+#[derive(Accounts)]
+pub struct RefreshAnimal<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle,
+        has_one = rewards_account
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The staking account
+    #[account(
+        mut,
+        seeds = [
+            b"animal".as_ref(),
+            jungle.key.as_ref(),
+            animal.mint.as_ref()
+        ],
+        bump = animal.bumps.animal,
+        has_one = jungle
+    )]
+    pub animal: Account<'info, Animal>,
+
+    /// The attributes PDA of the token, whose registered attributes take precedence over the leaf
+    pub attributes: AccountInfo<'info>,
+
+    /// The staker's account receiving the rewards earned at the previous rarity
+    #[account(
+        mut,
+        constraint =
+            staker_account.owner == animal.staker &&
+            staker_account.mint == jungle.mint
+    )]
+    pub staker_account: Account<'info, TokenAccount>,
+
+    /// The account holding the staking rewards
+    #[account(mut)]
+    pub rewards_account: Account<'info, TokenAccount>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Pays the pending rewards of a stale animal, then updates its rarity and faction from the current root,
+/// or from the registry if the mint is registered
+pub fn handler(
+    ctx: Context<RefreshAnimal>,
+    proof: Vec<[u8; 32]>,
+    rarity: u64,
+    faction: Faction,
+) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    let animal = &mut ctx.accounts.animal;
+    if jungle.is_paused(PAUSE_CLAIM) {
+        return Err(ErrorCode::Paused.into());
+    }
+
+    if animal.root_version >= jungle.root_version {
+        return Err(ErrorCode::AnimalNotStale.into());
+    }
+
+    let registered = AnimalAttributes::load(
+        &ctx.accounts.attributes,
+        &jungle.key,
+        &animal.mint,
+        ctx.program_id,
+    )?;
+    let (rarity, faction) = jungle.attributes(registered, &animal.mint, rarity, faction, |root, node| {
+        merkle_proof::verify(proof, root, node)
+    })?;
+
+    // Settle the rewards earned at the previous rarity
+    let now = ctx.accounts.clock.unix_timestamp;
+    jungle.update_rewards(now)?;
+    let rewards_amount = jungle.settle(animal)?;
+    animal.last_claim = now;
+
+    emit!(AnimalRefreshed {
+        jungle: jungle.key(),
+        mint: animal.mint,
+        old_rarity: animal.rarity,
+        new_rarity: rarity,
+        old_faction: animal.faction,
+        new_faction: faction,
+        rewards: rewards_amount,
+        ts: now,
+    });
+
+    let lock_tier = jungle.lock_tiers[animal.lock_tier as usize];
+    let weight = jungle.weight(rarity, &lock_tier)?;
+
+    // The animal may change faction, so it leaves the totals and comes back with its new attributes
+    jungle.remove_animal(animal)?;
+    animal.rarity = rarity;
+    animal.faction = faction;
+    animal.weight = weight;
+    jungle.add_animal(animal)?;
+    animal.root_version = jungle.root_version;
+    animal.reward_debt = animal.accrued_rewards(jungle)?;
+
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle.key.as_ref(),
+        &[jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    let context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.rewards_account.to_account_info(),
+            to: ctx.accounts.staker_account.to_account_info(),
+            authority: ctx.accounts.escrow.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(context, rewards_amount)?;
+
+    msg!("Animal refreshed");
+
+    Ok(())
+}
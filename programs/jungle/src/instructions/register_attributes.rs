@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::events::AttributesRegistered;
+use crate::{create_pda, AnimalAttributes, AttributesEntry, Jungle};
+
+#[derive(Accounts)]
+pub struct RegisterAttributes<'info> {
+    /// The Jungle
+    #[account(
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle, paying for the new attributes accounts
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates or overwrites the attributes accounts passed as remaining accounts
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterAttributes<'info>>,
+    entries: Vec<AttributesEntry>,
+) -> ProgramResult {
+    if ctx.remaining_accounts.len() != entries.len() {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+
+    let jungle_key = ctx.accounts.jungle.key;
+    for (entry, info) in entries.iter().zip(ctx.remaining_accounts.iter()) {
+        let seeds: &[&[u8]] = &[
+            b"attributes",
+            jungle_key.as_ref(),
+            entry.mint.as_ref(),
+            &[entry.bump],
+        ];
+        // Staking only reads the canonical PDA
+        let (address, bump) = Pubkey::find_program_address(&seeds[..3], ctx.program_id);
+        if address != info.key() || bump != entry.bump {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        if info.data_is_empty() {
+            create_pda(
+                &ctx.accounts.owner.to_account_info(),
+                info,
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.rent,
+                seeds,
+                AnimalAttributes::LEN,
+                ctx.program_id,
+            )?;
+        } else if info.owner != ctx.program_id {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        let attributes = AnimalAttributes {
+            bump: entry.bump,
+            mint: entry.mint,
+            rarity: entry.rarity,
+            faction: entry.faction,
+        };
+        let mut data: &mut [u8] = &mut info.try_borrow_mut_data()?;
+        attributes.try_serialize(&mut data)?;
+
+        emit!(AttributesRegistered {
+            jungle: ctx.accounts.jungle.key(),
+            mint: entry.mint,
+            rarity: entry.rarity,
+            faction: entry.faction,
+        });
+    }
+
+    msg!("Attributes registered");
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::events::AnimalsReweighted;
+use crate::{Animal, Jungle};
+
+#[derive(Accounts)]
+pub struct ReweightAnimals<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Moves the staked animals passed as remaining accounts to the weights of the current epoch
+/// Their rewards so far stay pending, and anyone can call it so no animal keeps an outdated weight
+pub fn handler(ctx: Context<ReweightAnimals>) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+
+    for info in ctx.remaining_accounts.iter() {
+        let mut animal: Account<Animal> = Account::try_from(info)?;
+        if animal.jungle != jungle.key() {
+            return Err(ErrorCode::InvalidAnimal.into());
+        }
+        if animal.epoch == jungle.epoch {
+            continue;
+        }
+
+        // Settles the rewards at the previous weight without paying them
+        jungle.spend(&mut animal, 0)?;
+
+        // Persist right away so an animal passed twice is only reweighted once
+        animal.exit(ctx.program_id)?;
+    }
+
+    emit!(AnimalsReweighted {
+        jungle: jungle.key(),
+        epoch: jungle.epoch,
+        stale_animals: jungle.stale_animals,
+    });
+
+    msg!("Animals reweighted");
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::events::ClaimDelegateSet;
+use crate::StakerProfile;
+
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    /// The profile of the staker
+    #[account(
+        mut,
+        has_one = staker,
+    )]
+    pub profile: Account<'info, StakerProfile>,
+
+    /// The owner of the animals
+    pub staker: Signer<'info>,
+}
+
+/// Sets the claim delegate and recipient of the staker, null keys disabling them
+pub fn handler(ctx: Context<SetClaimDelegate>, delegate: Pubkey, recipient: Pubkey) -> ProgramResult {
+    let profile = &mut ctx.accounts.profile;
+    // The profile stores the jungle's identifier, events carry its address
+    let (jungle, _) = Pubkey::find_program_address(&[b"jungle", profile.jungle.as_ref()], ctx.program_id);
+    emit!(ClaimDelegateSet {
+        jungle,
+        staker: profile.staker,
+        old_delegate: profile.delegate,
+        new_delegate: delegate,
+        old_recipient: profile.recipient,
+        new_recipient: recipient,
+    });
+
+    profile.delegate = delegate;
+    profile.recipient = recipient;
+
+    msg!("Claim delegate set");
+
+    Ok(())
+}
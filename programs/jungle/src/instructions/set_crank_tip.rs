@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::events::CrankTipSet;
+use crate::Jungle;
+
+#[derive(Accounts)]
+pub struct SetCrankTip<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+}
+
+/// Sets the tip paid to the callers of `crank_claim`, zero disabling it
+pub fn handler(ctx: Context<SetCrankTip>, crank_tip: u64) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    let old_crank_tip = jungle.crank_tip;
+    jungle.crank_tip = crank_tip;
+
+    emit!(CrankTipSet {
+        jungle: jungle.key(),
+        old_crank_tip,
+        new_crank_tip: crank_tip,
+    });
+
+    msg!("Crank tip set");
+
+    Ok(())
+}
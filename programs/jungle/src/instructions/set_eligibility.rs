@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::events::EligibilitySet;
+use crate::{Eligibility, Jungle};
+
+#[derive(Accounts)]
+pub struct SetEligibility<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+}
+
+/// Sets the eligibility mode and the collection key it checks
+pub fn handler(
+    ctx: Context<SetEligibility>,
+    eligibility: Eligibility,
+    collection: Pubkey,
+) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    emit!(EligibilitySet {
+        jungle: jungle.key(),
+        old_eligibility: jungle.eligibility,
+        new_eligibility: eligibility,
+        old_collection: jungle.collection,
+        new_collection: collection,
+    });
+
+    jungle.eligibility = eligibility;
+    jungle.collection = collection;
+
+    msg!("Eligibility set");
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::events::EmissionScheduleSet;
+use crate::{errors::ErrorCode, EmissionBreakpoint, Jungle, EMISSION_BREAKPOINTS};
+
+#[derive(Accounts)]
+pub struct SetEmissionSchedule<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Replaces the emission schedule of the jungle
+pub fn handler(
+    ctx: Context<SetEmissionSchedule>,
+    schedule: [EmissionBreakpoint; EMISSION_BREAKPOINTS],
+) -> ProgramResult {
+    let sorted = schedule.windows(2).all(|pair| {
+        match (pair[0].is_enabled(), pair[1].is_enabled()) {
+            (true, true) => pair[1].start > pair[0].start,
+            (false, true) => false,
+            _ => true,
+        }
+    });
+    if !sorted {
+        return Err(ErrorCode::InvalidSchedule.into());
+    }
+
+    // Pay the emissions so far with the previous schedule
+    let jungle = &mut ctx.accounts.jungle;
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+    emit!(EmissionScheduleSet {
+        jungle: jungle.key(),
+        old_schedule: jungle.emission_schedule,
+        new_schedule: schedule,
+    });
+    jungle.emission_schedule = schedule;
+
+    msg!("Emission schedule set");
+
+    Ok(())
+}
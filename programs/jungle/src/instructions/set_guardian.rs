@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::events::GuardianSet;
+use crate::Jungle;
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+
+    /// The wallet that will be able to pause the jungle
+    pub guardian: AccountInfo<'info>,
+}
+
+/// Sets the guardian of the jungle
+pub fn handler(ctx: Context<SetGuardian>) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    emit!(GuardianSet {
+        jungle: jungle.key(),
+        old_guardian: jungle.guardian,
+        new_guardian: ctx.accounts.guardian.key(),
+    });
+
+    jungle.guardian = ctx.accounts.guardian.key();
+
+    msg!("Guardian set");
+
+    Ok(())
+}
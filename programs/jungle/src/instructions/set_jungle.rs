@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
-use crate::{errors::ErrorCode, Jungle};
+use crate::events::JungleSet;
+use crate::{errors::ErrorCode, Jungle, LockTier, StakingMode, LOCK_TIERS};
 
 #[derive(Accounts)]
 pub struct SetJungle<'info> {
@@ -13,12 +14,13 @@ pub struct SetJungle<'info> {
 
     /// The wallet that owns the jungle
     pub owner: Signer<'info>,
-    
-    /// The wallet that will own the jungle
-    pub new_owner: AccountInfo<'info>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
 }
 
 /// Sets the jungle parameters
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<SetJungle>,
     max_rarity: u64,
@@ -26,18 +28,69 @@ pub fn handler(
     base_weekly_emissions: u64,
     start: i64,
     root: [u8; 32],
+    staking_mode: StakingMode,
+    lock_tiers: [LockTier; LOCK_TIERS],
 ) -> ProgramResult {
     if max_multiplier < 10000 {
         return Err(ErrorCode::InvalidMultiplier.into())
     }
+    if max_rarity == 0 {
+        return Err(ErrorCode::InvalidRarity.into())
+    }
 
+    // Disabled tiers have a null multiplier, others can only boost rewards
+    for tier in lock_tiers.iter() {
+        if tier.duration < 0 || (tier.multiplier != 0 && tier.multiplier < 10000) {
+            return Err(ErrorCode::InvalidLockTier.into())
+        }
+    }
+
+    // Pay the emissions so far with the previous parameters
     let jungle = &mut ctx.accounts.jungle;
-    jungle.owner = ctx.accounts.new_owner.key();
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+
+    // Animals move to the new weights when they are next settled or reweighted
+    let weights_changed = jungle.maximum_rarity != max_rarity
+        || jungle.maximum_rarity_multiplier != max_multiplier
+        || jungle
+            .lock_tiers
+            .iter()
+            .zip(lock_tiers.iter())
+            .any(|(old, new)| old.multiplier != new.multiplier);
+    if weights_changed {
+        jungle.new_epoch()?;
+    }
+
+    // Staked animals keep their rarity until refreshed against the new root
+    if jungle.root != root {
+        jungle.root_version = jungle.root_version.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    emit!(JungleSet {
+        jungle: jungle.key(),
+        old_maximum_rarity: jungle.maximum_rarity,
+        new_maximum_rarity: max_rarity,
+        old_maximum_rarity_multiplier: jungle.maximum_rarity_multiplier,
+        new_maximum_rarity_multiplier: max_multiplier,
+        old_base_weekly_emissions: jungle.base_weekly_emissions,
+        new_base_weekly_emissions: base_weekly_emissions,
+        old_start: jungle.start,
+        new_start: start,
+        old_root: jungle.root,
+        new_root: root,
+        old_staking_mode: jungle.staking_mode,
+        new_staking_mode: staking_mode,
+        old_lock_tiers: jungle.lock_tiers,
+        new_lock_tiers: lock_tiers,
+    });
+
     jungle.maximum_rarity = max_rarity;
     jungle.maximum_rarity_multiplier = max_multiplier;
     jungle.base_weekly_emissions = base_weekly_emissions;
     jungle.start = start;
     jungle.root = root;
+    jungle.staking_mode = staking_mode;
+    jungle.lock_tiers = lock_tiers;
 
     msg!("Jungle set");
 
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::events::PauseSet;
+use crate::{Jungle, PAUSE_ALL};
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        constraint = authority.key() == jungle.owner || authority.key() == jungle.guardian
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The owner or the guardian of the jungle
+    pub authority: Signer<'info>,
+}
+
+/// Pauses or resumes operations of the jungle
+/// Unstaking is never paused so holders can always get their animals back
+/// The guardian can only add flags, resuming operations is left to the owner
+pub fn handler(ctx: Context<SetPause>, paused: u8) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    let old_paused = jungle.paused;
+    if paused & !PAUSE_ALL != 0 {
+        return Err(ErrorCode::InvalidPause.into());
+    }
+    if ctx.accounts.authority.key() != jungle.owner && paused & old_paused != old_paused {
+        return Err(ErrorCode::InvalidPause.into());
+    }
+    jungle.paused = paused;
+
+    emit!(PauseSet {
+        jungle: jungle.key(),
+        old_paused,
+        new_paused: paused,
+    });
+
+    msg!("Pause set");
+
+    Ok(())
+}
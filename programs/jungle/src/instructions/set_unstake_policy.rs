@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::events::UnstakePolicySet;
+use crate::{Jungle, UnstakePolicy};
+
+#[derive(Accounts)]
+pub struct SetUnstakePolicy<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+}
+
+/// Sets what happens to the pending rewards of animals unstaked without paying them
+pub fn handler(ctx: Context<SetUnstakePolicy>, unstake_policy: UnstakePolicy) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    let old_unstake_policy = jungle.unstake_policy;
+    jungle.unstake_policy = unstake_policy;
+
+    emit!(UnstakePolicySet {
+        jungle: jungle.key(),
+        old_unstake_policy,
+        new_unstake_policy: unstake_policy,
+    });
+
+    msg!("Unstake policy set");
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::events::WithdrawalLimitsSet;
+use crate::{errors::ErrorCode, Jungle};
+
+#[derive(Accounts)]
+pub struct SetWithdrawalLimits<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The wallet that owns the jungle
+    pub owner: Signer<'info>,
+}
+
+/// Sets the withdrawal delay and weekly cap
+pub fn handler(ctx: Context<SetWithdrawalLimits>, delay: i64, cap: u64) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+
+    // Loosening the limits would let the owner skip them right away
+    let cap_tightened = match (jungle.withdrawal_cap, cap) {
+        (_, 0) => jungle.withdrawal_cap == 0,
+        (0, _) => true,
+        (old, new) => new <= old,
+    };
+    if delay < jungle.withdrawal_delay || !cap_tightened {
+        return Err(ErrorCode::InvalidWithdrawalLimits.into());
+    }
+
+    emit!(WithdrawalLimitsSet {
+        jungle: jungle.key(),
+        old_delay: jungle.withdrawal_delay,
+        new_delay: delay,
+        old_cap: jungle.withdrawal_cap,
+        new_cap: cap,
+    });
+
+    jungle.withdrawal_delay = delay;
+    jungle.withdrawal_cap = cap;
+
+    msg!("Withdrawal limits set");
+
+    Ok(())
+}
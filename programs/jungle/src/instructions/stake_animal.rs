@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::{Jungle, Animal, StakeAnimalBumps};
+use crate::events::AnimalStaked;
+use crate::{Jungle, Animal, AnimalAttributes, AnimalLeaf, Faction, StakeAnimalBumps, StakingMode};
 use crate::merkle_proof;
 use crate::errors::*;
 
@@ -31,12 +32,12 @@ pub struct StakeAnimal<'info> {
     pub escrow: AccountInfo<'info>,
 
     /// The created staking account
-    /// Doesn't use jungle.key as one token can only be staked once 
     #[account(
         init,
         payer = staker,
         seeds = [
             b"animal",
+            jungle.key.as_ref(),
             mint.key().as_ref()
         ],
         bump = bumps.animal,
@@ -59,13 +60,19 @@ pub struct StakeAnimal<'info> {
     )]
     pub staker_account: Account<'info, TokenAccount>,
 
+    /// The Metaplex metadata of the token, only used by the metadata eligibility modes
+    pub metadata: AccountInfo<'info>,
+
+    /// The attributes PDA of the token, read if the mint was registered
+    pub attributes: AccountInfo<'info>,
+
     /// The account that will hold the token being staked
-    /// Doesn't use jungle.key as one token can only be staked once
     #[account(
         init,
         payer = staker,
         seeds = [
             b"deposit",
+            jungle.key.as_ref(),
             mint.key().as_ref()
         ],
         bump = bumps.deposit,
@@ -104,35 +111,52 @@ pub fn handler(
     bumps: StakeAnimalBumps,
     proof: Vec<[u8; 32]>,
     rarity: u64,
-    faction: u64
+    faction: Faction,
+    lock_tier: u8,
 ) -> ProgramResult {
     let jungle = &mut ctx.accounts.jungle;
-
-    // Check that staking started
-    if jungle.start > ctx.accounts.clock.unix_timestamp {
-        return Err(ErrorCode::TooEarly.into());
-    }
-
-    // Verify the merkle leaf
-    let node = solana_program::keccak::hashv(&[
-        &[0x00],
-        &ctx.accounts.mint.key().to_bytes(),
-        &rarity.to_le_bytes(),
-        &faction.to_le_bytes(),
-    ]);
-    if !merkle_proof::verify(proof, jungle.root, node.0) {
-        return Err(ErrorCode::InvalidProof.into());
+    if jungle.staking_mode != StakingMode::Deposit {
+        return Err(ErrorCode::InvalidStakingMode.into());
     }
 
-    jungle.animals_staked += 1;
+    let registered = AnimalAttributes::load(
+        &ctx.accounts.attributes,
+        &jungle.key,
+        &ctx.accounts.mint.key(),
+        ctx.program_id,
+    )?;
 
     let animal = &mut ctx.accounts.animal;
-    animal.bumps = bumps;
+    animal.jungle = jungle.key();
     animal.mint = ctx.accounts.mint.key();
     animal.staker = ctx.accounts.staker.key();
-    animal.last_claim = ctx.accounts.clock.unix_timestamp;
-    animal.rarity = rarity;
-    animal.faction = faction as u8;
+    animal.mode = StakingMode::Deposit;
+    jungle.stake(
+        animal,
+        &AnimalLeaf {
+            bumps,
+            rarity,
+            faction,
+            lock_tier,
+            ..Default::default()
+        },
+        registered,
+        |root, node| merkle_proof::verify(proof, root, node),
+        Some(&ctx.accounts.metadata),
+        ctx.accounts.clock.unix_timestamp,
+    )?;
+
+    emit!(AnimalStaked {
+        jungle: animal.jungle,
+        mint: animal.mint,
+        staker: animal.staker,
+        rarity: animal.rarity,
+        faction: animal.faction,
+        mode: animal.mode,
+        lock_tier: animal.lock_tier,
+        locked_until: animal.locked_until,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
 
     token::transfer(ctx.accounts.transfer_context(), 1)?;
 
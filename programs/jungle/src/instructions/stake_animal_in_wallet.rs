@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Approve, Token, TokenAccount};
+
+use crate::errors::*;
+use crate::events::AnimalStaked;
+use crate::freeze::FrozenToken;
+use crate::merkle_proof;
+use crate::{Animal, AnimalAttributes, AnimalLeaf, Faction, Jungle, StakeAnimalBumps, StakingMode};
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct StakeAnimalInWallet<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The created staking account
+    #[account(
+        init,
+        payer = staker,
+        seeds = [
+            b"animal",
+            jungle.key.as_ref(),
+            mint.key().as_ref()
+        ],
+        bump = bump,
+    )]
+    pub animal: Account<'info, Animal>,
+
+    /// The owner of the token being staked
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// The mint of the token being staked
+    pub mint: AccountInfo<'info>,
+
+    /// The user account that holds the NFT and will be frozen
+    #[account(
+        mut,
+        has_one = mint,
+        constraint =
+            staker_account.owner == staker.key() &&
+            staker_account.amount == 1
+    )]
+    pub staker_account: Account<'info, TokenAccount>,
+
+    /// The Metaplex metadata of the token, only used by the metadata eligibility modes
+    pub metadata: AccountInfo<'info>,
+
+    /// The attributes PDA of the token, read if the mint was registered
+    pub attributes: AccountInfo<'info>,
+
+    /// The master edition of the token, only used to freeze through Metaplex
+    pub edition: AccountInfo<'info>,
+
+    /// The Metaplex token metadata program, only used to freeze through Metaplex
+    pub token_metadata_program: AccountInfo<'info>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeAnimalInWallet<'info> {
+    fn approve_context(&self) -> CpiContext<'_, '_, '_, 'info, Approve<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Approve {
+                to: self.staker_account.to_account_info(),
+                delegate: self.escrow.to_account_info(),
+                authority: self.staker.to_account_info(),
+            },
+        )
+    }
+
+    fn frozen_token(&self) -> FrozenToken<'info> {
+        FrozenToken {
+            escrow: self.escrow.to_account_info(),
+            token_account: self.staker_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            edition: self.edition.to_account_info(),
+            token_metadata_program: self.token_metadata_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        }
+    }
+}
+
+/// Stakes a token by freezing it in the staker's wallet
+pub fn handler(
+    ctx: Context<StakeAnimalInWallet>,
+    bump: u8,
+    proof: Vec<[u8; 32]>,
+    rarity: u64,
+    faction: Faction,
+    lock_tier: u8,
+) -> ProgramResult {
+    let jungle = &mut ctx.accounts.jungle;
+    let mode = jungle.staking_mode;
+    if mode == StakingMode::Deposit {
+        return Err(ErrorCode::InvalidStakingMode.into());
+    }
+
+    let registered = AnimalAttributes::load(
+        &ctx.accounts.attributes,
+        &jungle.key,
+        &ctx.accounts.mint.key(),
+        ctx.program_id,
+    )?;
+
+    let animal = &mut ctx.accounts.animal;
+    animal.jungle = jungle.key();
+    animal.mint = ctx.accounts.mint.key();
+    animal.staker = ctx.accounts.staker.key();
+    animal.mode = mode;
+    jungle.stake(
+        animal,
+        &AnimalLeaf {
+            bumps: StakeAnimalBumps {
+                animal: bump,
+                deposit: 0,
+            },
+            rarity,
+            faction,
+            lock_tier,
+            ..Default::default()
+        },
+        registered,
+        |root, node| merkle_proof::verify(proof, root, node),
+        Some(&ctx.accounts.metadata),
+        ctx.accounts.clock.unix_timestamp,
+    )?;
+
+    emit!(AnimalStaked {
+        jungle: animal.jungle,
+        mint: animal.mint,
+        staker: animal.staker,
+        rarity: animal.rarity,
+        faction: animal.faction,
+        mode: animal.mode,
+        lock_tier: animal.lock_tier,
+        locked_until: animal.locked_until,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
+
+    let jungle_key = jungle.key;
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle_key.as_ref(),
+        &[jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Let the escrow freeze the token in the staker's wallet
+    token::approve(ctx.accounts.approve_context(), 1)?;
+    ctx.accounts.frozen_token().freeze(mode, signer)?;
+
+    msg!("Token staked in wallet");
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, InitializeAccount, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::AnimalStaked;
+use crate::merkle_proof;
+use crate::{create_pda, leaf_node, Animal, AnimalAttributes, AnimalLeaf, Jungle, StakingMode};
+
+/// The accounts passed for each animal: animal, mint, staker account, deposit account and attributes PDA
+const ACCOUNTS_PER_ANIMAL: usize = 5;
+
+#[derive(Accounts)]
+pub struct StakeAnimals<'info> {
+    /// The jungle state
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The owner of the tokens being staked
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeAnimals<'info> {
+    /// Creates a PDA of the program `owner`, paid by the staker
+    fn create_account(
+        &self,
+        program_id: &Pubkey,
+        account: &AccountInfo<'info>,
+        seeds: &[&[u8]],
+        space: usize,
+        owner: &Pubkey,
+    ) -> ProgramResult {
+        let address = Pubkey::create_program_address(seeds, program_id)?;
+        if address != account.key() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        create_pda(
+            &self.staker.to_account_info(),
+            account,
+            &self.system_program.to_account_info(),
+            &self.rent,
+            seeds,
+            space,
+            owner,
+        )
+    }
+}
+
+/// Stakes all the animals passed as remaining accounts
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, StakeAnimals<'info>>,
+    leaf_count: u32,
+    proof: Vec<[u8; 32]>,
+    leaves: Vec<AnimalLeaf>,
+) -> ProgramResult {
+    let now = ctx.accounts.clock.unix_timestamp;
+    if ctx.accounts.jungle.staking_mode != StakingMode::Deposit {
+        return Err(ErrorCode::InvalidStakingMode.into());
+    }
+
+    if ctx.remaining_accounts.len() != leaves.len() * ACCOUNTS_PER_ANIMAL {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+
+    // Read the registry, then verify the leaves of the mints that aren't registered all at once
+    let jungle_key = ctx.accounts.jungle.key;
+    let mut registry = Vec::with_capacity(leaves.len());
+    let mut nodes = Vec::new();
+    for (leaf, accounts) in leaves
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(ACCOUNTS_PER_ANIMAL))
+    {
+        let registered = AnimalAttributes::load(&accounts[4], &jungle_key, accounts[1].key, ctx.program_id)?;
+        if registered.is_none() {
+            nodes.push((leaf.index, leaf_node(accounts[1].key, leaf.rarity, leaf.faction)));
+        }
+        registry.push(registered);
+    }
+    let proven: Vec<[u8; 32]> = nodes.iter().map(|(_, node)| *node).collect();
+    if !nodes.is_empty() && !merkle_proof::verify_multi(proof, ctx.accounts.jungle.root, nodes, leaf_count) {
+        return Err(ErrorCode::InvalidProof.into());
+    }
+
+    for ((leaf, accounts), registered) in leaves
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(ACCOUNTS_PER_ANIMAL))
+        .zip(registry)
+    {
+        let animal_info = &accounts[0];
+        let mint = &accounts[1];
+        let deposit_account = &accounts[3];
+
+        let staker_account: Account<TokenAccount> = Account::try_from(&accounts[2])?;
+        if staker_account.mint != mint.key() || staker_account.owner != ctx.accounts.staker.key() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        // Create the staking account
+        ctx.accounts.create_account(
+            ctx.program_id,
+            animal_info,
+            &[b"animal", jungle_key.as_ref(), mint.key.as_ref(), &[leaf.bumps.animal]],
+            Animal::LEN,
+            ctx.program_id,
+        )?;
+
+        // Create the account that will hold the token being staked
+        ctx.accounts.create_account(
+            ctx.program_id,
+            deposit_account,
+            &[b"deposit", jungle_key.as_ref(), mint.key.as_ref(), &[leaf.bumps.deposit]],
+            TokenAccount::LEN,
+            &token::ID,
+        )?;
+        token::initialize_account(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeAccount {
+                account: deposit_account.clone(),
+                mint: mint.clone(),
+                authority: ctx.accounts.escrow.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+        ))?;
+
+        // The leaf was proven with the rest of the batch
+        let jungle = &mut ctx.accounts.jungle;
+        let mut animal = Animal {
+            jungle: jungle.key(),
+            mint: mint.key(),
+            staker: ctx.accounts.staker.key(),
+            mode: StakingMode::Deposit,
+            ..Default::default()
+        };
+        jungle.stake(&mut animal, leaf, registered, |_, node| proven.contains(&node), None, now)?;
+
+        emit!(AnimalStaked {
+            jungle: animal.jungle,
+            mint: animal.mint,
+            staker: animal.staker,
+            rarity: animal.rarity,
+            faction: animal.faction,
+            mode: animal.mode,
+            lock_tier: animal.lock_tier,
+            locked_until: animal.locked_until,
+            ts: now,
+        });
+
+        let mut data: &mut [u8] = &mut animal_info.try_borrow_mut_data()?;
+        animal.try_serialize(&mut data)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: staker_account.to_account_info(),
+                    to: deposit_account.clone(),
+                    authority: ctx.accounts.staker.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+    }
+
+    msg!("Tokens staked");
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::StakeTransferred;
+use crate::{Animal, Jungle, StakingMode, PAUSE_CLAIM};
+
+#[derive(Accounts)]
+pub struct TransferStake<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The staking account
+    #[account(
+        mut,
+        seeds = [
+            b"animal".as_ref(),
+            jungle.key.as_ref(),
+            animal.mint.as_ref()
+        ],
+        bump = animal.bumps.animal,
+        has_one = jungle,
+        has_one = staker
+    )]
+    pub animal: Account<'info, Animal>,
+
+    /// The current owner of the animal
+    pub staker: Signer<'info>,
+
+    /// The wallet receiving the animal
+    pub new_staker: AccountInfo<'info>,
+
+    /// The account holding the staking rewards, only used to pay the pending rewards
+    #[account(mut)]
+    pub rewards_account: AccountInfo<'info>,
+
+    /// The staker's account receiving the pending rewards, only used to pay them
+    #[account(mut)]
+    pub staker_rewards_account: AccountInfo<'info>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Hands the animal to a new staker, the pending rewards going to the previous one if paid
+pub fn handler(ctx: Context<TransferStake>) -> ProgramResult {
+    let now = ctx.accounts.clock.unix_timestamp;
+    let jungle = &mut ctx.accounts.jungle;
+    let animal = &mut ctx.accounts.animal;
+
+    // A frozen token stays in the wallet of the staker, so only deposits can change hands
+    if animal.mode != StakingMode::Deposit {
+        return Err(ErrorCode::InvalidStakingMode.into());
+    }
+
+    // The pending rewards are paid if the rewards accounts are passed, otherwise they follow the animal
+    let mut rewards_amount = 0;
+    if ctx.accounts.rewards_account.key() == jungle.rewards_account {
+        if jungle.is_paused(PAUSE_CLAIM) {
+            return Err(ErrorCode::Paused.into());
+        }
+        let staker_rewards_account: Account<TokenAccount> =
+            Account::try_from(&ctx.accounts.staker_rewards_account)?;
+        if staker_rewards_account.mint != jungle.mint
+            || staker_rewards_account.owner != ctx.accounts.staker.key()
+        {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        jungle.update_rewards(now)?;
+        rewards_amount = jungle.settle(animal)?;
+        animal.last_claim = now;
+
+        let seeds = &[
+            b"escrow".as_ref(),
+            jungle.key.as_ref(),
+            &[jungle.bumps.escrow],
+        ];
+        let signer = &[&seeds[..]];
+        let context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.rewards_account.clone(),
+                to: ctx.accounts.staker_rewards_account.clone(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(context, rewards_amount)?;
+    }
+
+    animal.staker = ctx.accounts.new_staker.key();
+
+    emit!(StakeTransferred {
+        jungle: jungle.key(),
+        mint: animal.mint,
+        from: ctx.accounts.staker.key(),
+        to: animal.staker,
+        rewards: rewards_amount,
+        ts: now,
+    });
+
+    msg!("Stake transferred");
+
+    Ok(())
+}
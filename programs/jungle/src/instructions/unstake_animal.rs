@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
 use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
 
-use crate::{Animal, Jungle};
+use crate::errors::*;
+use crate::events::AnimalUnstaked;
+use crate::freeze::FrozenToken;
+use crate::{Animal, Jungle, StakingMode};
 
 #[derive(Accounts)]
 pub struct UnstakeAnimal<'info> {
@@ -18,6 +22,7 @@ pub struct UnstakeAnimal<'info> {
 
     /// The account holding staking tokens, staking rewards and community funds
     #[account(
+        mut,
         seeds = [
             b"escrow",
             jungle.key.as_ref()
@@ -30,6 +35,7 @@ pub struct UnstakeAnimal<'info> {
     #[account(
         mut,
         close = staker,
+        has_one = jungle,
         has_one = mint,
         has_one = staker
     )]
@@ -51,27 +57,50 @@ pub struct UnstakeAnimal<'info> {
     )]
     pub staker_account: Account<'info, TokenAccount>,
 
-    /// The account that holds the staked token
-    #[account(
-        mut,
-        seeds = [
-            b"deposit".as_ref(),
-            mint.key().as_ref()
-        ],
-        bump = animal.bumps.deposit,
-        has_one = mint
-    )]
-    pub deposit_account: Account<'info, TokenAccount>,
+    /// The account that holds the staked token, only used in deposit mode
+    #[account(mut)]
+    pub deposit_account: AccountInfo<'info>,
+
+    /// The master edition of the token, only used to thaw through Metaplex
+    pub edition: AccountInfo<'info>,
+
+    /// The Metaplex token metadata program, only used to thaw through Metaplex
+    pub token_metadata_program: AccountInfo<'info>,
+
+    /// The account holding the staking rewards, only used to pay the pending rewards
+    #[account(mut)]
+    pub rewards_account: AccountInfo<'info>,
+
+    /// The staker's account receiving the pending rewards, only used to pay them
+    #[account(mut)]
+    pub staker_rewards_account: AccountInfo<'info>,
 
     /// The program for interacting with the token.
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
 }
 
 /// Unstake the animal
 pub fn handler(ctx: Context<UnstakeAnimal>) -> ProgramResult {
+    if ctx.accounts.clock.unix_timestamp < ctx.accounts.animal.locked_until {
+        return Err(ErrorCode::StillLocked.into());
+    }
+
     let jungle = &mut ctx.accounts.jungle;
-    jungle.animals_staked -= 1;
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+
+    // The pending rewards are paid if the rewards accounts are passed
+    let pay = ctx.accounts.rewards_account.key() == jungle.rewards_account;
+    let vault = if pay {
+        Some(token::accessor::amount(&ctx.accounts.rewards_account)?)
+    } else {
+        None
+    };
+    let rewards_amount = jungle.settle_unstake(&mut ctx.accounts.animal, vault)?;
+    jungle.remove_animal(&ctx.accounts.animal)?;
 
     let seeds = &[
         b"escrow".as_ref(),
@@ -80,29 +109,102 @@ pub fn handler(ctx: Context<UnstakeAnimal>) -> ProgramResult {
     ];
     let signer = &[&seeds[..]];
 
-    // Return the animal NFT
-    let context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.deposit_account.to_account_info(),
-            to: ctx.accounts.staker_account.to_account_info(),
-            authority: ctx.accounts.escrow.to_account_info(),
-        },
-        signer,
-    );
-    token::transfer(context, 1)?;
-
-    // Close the staking token account
-    let close_account_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        CloseAccount {
-            account: ctx.accounts.deposit_account.to_account_info(),
-            destination: ctx.accounts.staker.to_account_info(),
-            authority: ctx.accounts.escrow.to_account_info(),
-        },
-        signer,
-    );
-    token::close_account(close_account_ctx)?;
+    if pay {
+        let staker_rewards_account: Account<TokenAccount> =
+            Account::try_from(&ctx.accounts.staker_rewards_account)?;
+        if staker_rewards_account.mint != jungle.mint
+            || staker_rewards_account.owner != ctx.accounts.staker.key()
+        {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        let context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.rewards_account.clone(),
+                to: ctx.accounts.staker_rewards_account.clone(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(context, rewards_amount)?;
+    }
+
+    match ctx.accounts.animal.mode {
+        StakingMode::Deposit => {
+            let deposit_address = Pubkey::create_program_address(
+                &[
+                    b"deposit".as_ref(),
+                    jungle.key.as_ref(),
+                    ctx.accounts.mint.key().as_ref(),
+                    &[ctx.accounts.animal.bumps.deposit],
+                ],
+                ctx.program_id,
+            )?;
+            if deposit_address != ctx.accounts.deposit_account.key() {
+                return Err(ErrorCode::InvalidAccounts.into());
+            }
+
+            // Return the animal NFT
+            let context = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.deposit_account.to_account_info(),
+                    to: ctx.accounts.staker_account.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(context, 1)?;
+
+            // Close the staking token account
+            let close_account_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.deposit_account.to_account_info(),
+                    destination: ctx.accounts.staker.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer,
+            );
+            token::close_account(close_account_ctx)?;
+        }
+        mode => {
+            // Release the animal NFT in the staker's wallet
+            let frozen_token = FrozenToken {
+                escrow: ctx.accounts.escrow.to_account_info(),
+                token_account: ctx.accounts.staker_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                edition: ctx.accounts.edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            };
+            frozen_token.thaw(mode, signer)?;
+
+            let revoke = spl_token::instruction::revoke(
+                &token::ID,
+                &ctx.accounts.staker_account.key(),
+                &ctx.accounts.staker.key(),
+                &[],
+            )?;
+            invoke(
+                &revoke,
+                &[
+                    ctx.accounts.staker_account.to_account_info(),
+                    ctx.accounts.staker.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
+    emit!(AnimalUnstaked {
+        jungle: jungle.key(),
+        mint: ctx.accounts.animal.mint,
+        staker: ctx.accounts.staker.key(),
+        rewards: rewards_amount,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
 
     msg!("Unstaked token");
 
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+use crate::errors::*;
+use crate::events::AnimalUnstaked;
+use crate::{Animal, Jungle, StakingMode};
+
+/// The accounts passed for each animal: animal, mint, staker account and deposit account
+const ACCOUNTS_PER_ANIMAL: usize = 4;
+
+#[derive(Accounts)]
+pub struct UnstakeAnimals<'info> {
+    /// The Jungle
+    #[account(
+        mut,
+        seeds = [
+            b"jungle",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.jungle
+    )]
+    pub jungle: Account<'info, Jungle>,
+
+    /// The account holding staking tokens, staking rewards and community funds
+    #[account(
+        seeds = [
+            b"escrow",
+            jungle.key.as_ref()
+        ],
+        bump = jungle.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The owner of the animals
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// The account holding the staking rewards, only used to pay the pending rewards
+    #[account(mut)]
+    pub rewards_account: AccountInfo<'info>,
+
+    /// The staker's account receiving the pending rewards, only used to pay them
+    #[account(mut)]
+    pub staker_rewards_account: AccountInfo<'info>,
+
+    /// The program for interacting with the token.
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Unstakes all the animals passed as remaining accounts
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UnstakeAnimals<'info>>) -> ProgramResult {
+    let animals = ctx.remaining_accounts.chunks_exact(ACCOUNTS_PER_ANIMAL);
+    if !animals.remainder().is_empty() {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+
+    let jungle = &mut ctx.accounts.jungle;
+    jungle.update_rewards(ctx.accounts.clock.unix_timestamp)?;
+
+    // The pending rewards are paid if the rewards accounts are passed
+    let pay = ctx.accounts.rewards_account.key() == jungle.rewards_account;
+    let vault = if pay {
+        Some(token::accessor::amount(&ctx.accounts.rewards_account)?)
+    } else {
+        None
+    };
+    let mut rewards_amount: u64 = 0;
+
+    let jungle_key = jungle.key;
+    let seeds = &[
+        b"escrow".as_ref(),
+        jungle_key.as_ref(),
+        &[jungle.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    for accounts in animals {
+        let mut animal: Account<Animal> = Account::try_from(&accounts[0])?;
+        if animal.mode != StakingMode::Deposit {
+            return Err(ErrorCode::InvalidStakingMode.into());
+        }
+        let mint = &accounts[1];
+        let deposit_account = &accounts[3];
+        if animal.jungle != jungle.key() || animal.mint != mint.key() {
+            return Err(ErrorCode::InvalidAnimal.into());
+        }
+        if animal.staker != ctx.accounts.staker.key() {
+            return Err(ErrorCode::InvalidStaker.into());
+        }
+        if ctx.accounts.clock.unix_timestamp < animal.locked_until {
+            return Err(ErrorCode::StillLocked.into());
+        }
+
+        let staker_account: Account<TokenAccount> = Account::try_from(&accounts[2])?;
+        if staker_account.mint != mint.key() || staker_account.owner != ctx.accounts.staker.key() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        let deposit_address = Pubkey::create_program_address(
+            &[b"deposit", jungle_key.as_ref(), mint.key.as_ref(), &[animal.bumps.deposit]],
+            ctx.program_id,
+        )?;
+        if deposit_address != deposit_account.key() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        // Each animal is paid from what the previous ones left in the vault
+        let left = vault.map(|balance| balance.saturating_sub(rewards_amount));
+        let animal_rewards = jungle.settle_unstake(&mut animal, left)?;
+        rewards_amount = rewards_amount
+            .checked_add(animal_rewards)
+            .ok_or(ErrorCode::MathOverflow)?;
+        jungle.remove_animal(&animal)?;
+
+        emit!(AnimalUnstaked {
+            jungle: jungle.key(),
+            mint: animal.mint,
+            staker: animal.staker,
+            rewards: animal_rewards,
+            ts: ctx.accounts.clock.unix_timestamp,
+        });
+
+        // Return the animal NFT
+        let context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: deposit_account.clone(),
+                to: staker_account.to_account_info(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(context, 1)?;
+
+        // Close the staking token account
+        let close_account_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: deposit_account.clone(),
+                destination: ctx.accounts.staker.to_account_info(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            signer,
+        );
+        token::close_account(close_account_ctx)?;
+
+        // Closing right away also makes an animal passed twice fail
+        animal.close(ctx.accounts.staker.to_account_info())?;
+    }
+
+    if pay {
+        let staker_rewards_account: Account<TokenAccount> =
+            Account::try_from(&ctx.accounts.staker_rewards_account)?;
+        if staker_rewards_account.mint != jungle.mint
+            || staker_rewards_account.owner != ctx.accounts.staker.key()
+        {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+
+        let context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.rewards_account.clone(),
+                to: ctx.accounts.staker_rewards_account.clone(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(context, rewards_amount)?;
+    }
+
+    msg!("Unstaked tokens");
+
+    Ok(())
+}
@@ -1,20 +1,124 @@
 #![cfg_attr(feature = "no-entrypoint", allow(dead_code))]
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use std::convert::TryFrom;
 
 pub mod errors;
+pub mod events;
+pub mod freeze;
 pub mod instructions;
 pub mod merkle_proof;
+pub mod metadata;
 
+use errors::ErrorCode;
 use instructions::*;
 
 declare_id!("8XgPs7DNb7jvZqu5Y6zbF1idvrXnLtHZK4kVGKALd9fS");
 
+/// The number of seconds in a week
+pub const WEEK: i64 = 604800;
+
+/// The scale of the accumulated rewards per weight, to keep precision on small amounts
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// The number of lock periods a staker can choose from
+pub const LOCK_TIERS: usize = 4;
+
+/// The maximum number of rate changes in the emission schedule
+pub const EMISSION_BREAKPOINTS: usize = 8;
+
+/// The number of factions, counting animals without one
+pub const FACTIONS: usize = 9;
+
+/// Pause flag blocking new stakes
+pub const PAUSE_STAKE: u8 = 1 << 0;
+
+/// Pause flag blocking reward claims
+pub const PAUSE_CLAIM: u8 = 1 << 1;
+
+/// Pause flag blocking withdrawals from the rewards account
+pub const PAUSE_WITHDRAW: u8 = 1 << 2;
+
+/// Every pause flag
+pub const PAUSE_ALL: u8 = PAUSE_STAKE | PAUSE_CLAIM | PAUSE_WITHDRAW;
+
+/// The withdrawal delay given to jungles migrated from the legacy layout (in seconds)
+pub const DEFAULT_WITHDRAWAL_DELAY: i64 = 172800;
+
+/// The maximum tip of a crank, in basis points of the rewards it pays
+pub const MAX_CRANK_TIP_BPS: u64 = 500;
+
+/// Narrows the result of a u128 intermediate computation
+pub fn to_u64(value: u128) -> Result<u64, ProgramError> {
+    u64::try_from(value).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// The Merkle leaf of a token with the given attributes
+pub fn leaf_node(mint: &Pubkey, rarity: u64, faction: Faction) -> [u8; 32] {
+    solana_program::keccak::hashv(&[
+        &[0x00],
+        &mint.to_bytes(),
+        &rarity.to_le_bytes(),
+        &(faction as u64).to_le_bytes(),
+    ])
+    .0
+}
+
+/// Creates the PDA `account` of the program `owner`, paid by `payer`
+///
+/// An address that already holds lamports can't be created with `create_account`,
+/// so it is topped up to the rent exemption, then allocated and assigned instead
+pub fn create_pda<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+    seeds: &[&[u8]],
+    space: usize,
+    owner: &Pubkey,
+) -> ProgramResult {
+    let required = rent.minimum_balance(space);
+    let current = account.lamports();
+    if current == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                required,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[seeds],
+        );
+    }
+
+    if current < required {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required - current),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )
+}
+
 #[program]
 mod jungle {
     use super::*;
 
     /// Initializes the jungle
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_jungle(
         ctx: Context<InitializeJungle>,
         bumps: InitializeJungleBumps,
@@ -23,6 +127,7 @@ mod jungle {
         base_weekly_emissions: u64,
         start: i64,
         root: [u8; 32],
+        withdrawal_delay: i64,
     ) -> ProgramResult {
         instructions::init_jungle::handler(
             ctx,
@@ -32,10 +137,13 @@ mod jungle {
             base_weekly_emissions,
             start,
             root,
+            withdrawal_delay,
         )
     }
 
     /// Sets the jungle parameters
+    /// The rewards earned so far are kept at the previous parameters
+    #[allow(clippy::too_many_arguments)]
     pub fn set_jungle(
         ctx: Context<SetJungle>,
         max_rarity: u64,
@@ -43,6 +151,8 @@ mod jungle {
         base_weekly_emissions: u64,
         start: i64,
         root: [u8; 32],
+        staking_mode: StakingMode,
+        lock_tiers: [LockTier; LOCK_TIERS],
     ) -> ProgramResult {
         instructions::set_jungle::handler(
             ctx,
@@ -51,18 +161,105 @@ mod jungle {
             base_weekly_emissions,
             start,
             root,
+            staking_mode,
+            lock_tiers,
         )
     }
 
-    /// Withdraw rewards from the vault
-    pub fn withdraw_rewards(
-        ctx: Context<WithdrawRewards>,
-        amount: u64
+    /// Sets the future changes of the weekly emissions
+    pub fn set_emission_schedule(
+        ctx: Context<SetEmissionSchedule>,
+        schedule: [EmissionBreakpoint; EMISSION_BREAKPOINTS],
     ) -> ProgramResult {
-        instructions::withdraw_rewards::handler(
-            ctx,
-            amount
-        )
+        instructions::set_emission_schedule::handler(ctx, schedule)
+    }
+
+    /// Proposes a new owner, who has to accept before getting control of the jungle
+    pub fn propose_owner(ctx: Context<ProposeOwner>) -> ProgramResult {
+        instructions::propose_owner::handler(ctx)
+    }
+
+    /// Accepts the ownership of the jungle proposed by the owner
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> ProgramResult {
+        instructions::accept_ownership::handler(ctx)
+    }
+
+    /// Cancels the pending ownership proposal
+    pub fn cancel_owner_proposal(ctx: Context<CancelOwnerProposal>) -> ProgramResult {
+        instructions::cancel_owner_proposal::handler(ctx)
+    }
+
+    /// Sets the wallet allowed to pause the jungle along with the owner
+    pub fn set_guardian(ctx: Context<SetGuardian>) -> ProgramResult {
+        instructions::set_guardian::handler(ctx)
+    }
+
+    /// Sets which operations are paused, as a combination of `PAUSE_*` flags
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> ProgramResult {
+        instructions::set_pause::handler(ctx, paused)
+    }
+
+    /// Sets how the program knows that a token is part of the collection
+    pub fn set_eligibility(
+        ctx: Context<SetEligibility>,
+        eligibility: Eligibility,
+        collection: Pubkey,
+    ) -> ProgramResult {
+        instructions::set_eligibility::handler(ctx, eligibility, collection)
+    }
+
+    /// Sets whether unstaking without paying the pending rewards forfeits them or fails
+    pub fn set_unstake_policy(
+        ctx: Context<SetUnstakePolicy>,
+        unstake_policy: UnstakePolicy,
+    ) -> ProgramResult {
+        instructions::set_unstake_policy::handler(ctx, unstake_policy)
+    }
+
+    /// Sets the timelock and the weekly cap of withdrawals, which can only be tightened
+    pub fn set_withdrawal_limits(
+        ctx: Context<SetWithdrawalLimits>,
+        delay: i64,
+        cap: u64,
+    ) -> ProgramResult {
+        instructions::set_withdrawal_limits::handler(ctx, delay, cap)
+    }
+
+    /// Queues a withdrawal of rewards from the vault, executable after the delay
+    pub fn queue_withdrawal(
+        ctx: Context<QueueWithdrawal>,
+        bump: u8,
+        amount: u64,
+    ) -> ProgramResult {
+        instructions::queue_withdrawal::handler(ctx, bump, amount)
+    }
+
+    /// Withdraw rewards from the vault once the queued withdrawal is unlocked
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> ProgramResult {
+        instructions::execute_withdrawal::handler(ctx)
+    }
+
+    /// Drops a queued withdrawal before it is executed
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> ProgramResult {
+        instructions::cancel_withdrawal::handler(ctx)
+    }
+
+    /// Registers the rarity and faction of several mints, their PDAs passed as remaining accounts
+    pub fn register_attributes<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterAttributes<'info>>,
+        entries: Vec<AttributesEntry>,
+    ) -> ProgramResult {
+        instructions::register_attributes::handler(ctx, entries)
+    }
+
+    /// Updates the rarity and faction of a staked animal from the current Merkle root
+    pub fn refresh_animal(
+        ctx: Context<RefreshAnimal>,
+        proof: Vec<[u8; 32]>,
+        rarity: u64,
+        faction: Faction,
+    ) -> ProgramResult {
+        instructions::refresh_animal::handler(ctx, proof, rarity, faction)
     }
 
     /// Stake an animal
@@ -71,9 +268,10 @@ mod jungle {
         bumps: StakeAnimalBumps,
         proof: Vec<[u8; 32]>,
         rarity: u64,
-        faction: u64,
+        faction: Faction,
+        lock_tier: u8,
     ) -> ProgramResult {
-        instructions::stake_animal::handler(ctx, bumps, proof, rarity, faction)
+        instructions::stake_animal::handler(ctx, bumps, proof, rarity, faction, lock_tier)
     }
 
     /// Unstake a staked animal
@@ -81,15 +279,97 @@ mod jungle {
         instructions::unstake_animal::handler(ctx)
     }
 
+    /// Hands a staked animal to another wallet, optionally paying the pending rewards first
+    pub fn transfer_stake(ctx: Context<TransferStake>) -> ProgramResult {
+        instructions::transfer_stake::handler(ctx)
+    }
+
+    /// Stake an animal without moving it from the staker's wallet
+    pub fn stake_animal_in_wallet(
+        ctx: Context<StakeAnimalInWallet>,
+        bump: u8,
+        proof: Vec<[u8; 32]>,
+        rarity: u64,
+        faction: Faction,
+        lock_tier: u8,
+    ) -> ProgramResult {
+        instructions::stake_animal_in_wallet::handler(ctx, bump, proof, rarity, faction, lock_tier)
+    }
+
+    /// Grows a jungle initialized before the rewards accumulator to the current layout
+    pub fn migrate_jungle(ctx: Context<MigrateJungle>) -> ProgramResult {
+        instructions::migrate_jungle::handler(ctx)
+    }
+
+    /// Moves an animal staked before the accounts were namespaced by jungle to its new accounts
+    pub fn migrate_animal(ctx: Context<MigrateAnimal>, bumps: StakeAnimalBumps) -> ProgramResult {
+        instructions::migrate_animal::handler(ctx, bumps)
+    }
+
+    /// Stake several animals, passed as remaining accounts, with a single proof
+    pub fn stake_animals<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeAnimals<'info>>,
+        leaf_count: u32,
+        proof: Vec<[u8; 32]>,
+        leaves: Vec<AnimalLeaf>,
+    ) -> ProgramResult {
+        instructions::stake_animals::handler(ctx, leaf_count, proof, leaves)
+    }
+
+    /// Unstake several animals, passed as remaining accounts
+    pub fn unstake_animals<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnstakeAnimals<'info>>,
+    ) -> ProgramResult {
+        instructions::unstake_animals::handler(ctx)
+    }
+
+    /// Creates the profile holding the claim settings of a staker
+    pub fn init_staker_profile(ctx: Context<InitStakerProfile>, bump: u8) -> ProgramResult {
+        instructions::init_staker_profile::handler(ctx, bump)
+    }
+
+    /// Sets the wallet allowed to claim the staker's rewards and the account they can be sent to
+    pub fn set_claim_delegate(
+        ctx: Context<SetClaimDelegate>,
+        delegate: Pubkey,
+        recipient: Pubkey,
+    ) -> ProgramResult {
+        instructions::set_claim_delegate::handler(ctx, delegate, recipient)
+    }
+
+    /// Sets the tip paid to the callers of `crank_claim`
+    pub fn set_crank_tip(ctx: Context<SetCrankTip>, crank_tip: u64) -> ProgramResult {
+        instructions::set_crank_tip::handler(ctx, crank_tip)
+    }
+
+    /// Pays the rewards of any animal to the staker's associated token account
+    pub fn crank_claim(ctx: Context<CrankClaim>) -> ProgramResult {
+        instructions::crank_claim::handler(ctx)
+    }
+
     /// Claim staking rewards
     pub fn claim_staking(ctx: Context<ClaimStaking>) -> ProgramResult {
         instructions::claim_staking::handler(ctx)
     }
 
-    // /// Enter the lottery by spending staking rewards
-    // pub fn enter_lottery(ctx: Context<Participate>, bump: u8, spendings: [u64; 8]) -> ProgramResult {
-    //     instructions::participate::handler(ctx, bump, spendings)
-    // }
+    /// Claim staking rewards of several animals, passed as remaining accounts
+    pub fn claim_staking_many(ctx: Context<ClaimStakingMany>) -> ProgramResult {
+        instructions::claim_staking_many::handler(ctx)
+    }
+
+    /// Moves the animals passed as remaining accounts to the weights of the current epoch
+    pub fn reweight_animals(ctx: Context<ReweightAnimals>) -> ProgramResult {
+        instructions::reweight_animals::handler(ctx)
+    }
+
+    /// Enter the lottery by spending the pending rewards of an animal
+    pub fn enter_lottery(
+        ctx: Context<EnterLottery>,
+        bump: u8,
+        spendings: [u64; 8],
+    ) -> ProgramResult {
+        instructions::enter_lottery::handler(ctx, bump, spendings)
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
@@ -131,7 +411,8 @@ pub struct Jungle {
     /// The rarity multiplier for staking rewards, in basis points
     pub maximum_rarity_multiplier: u64,
 
-    /// The amount of tokens emitted each week
+    /// The amount of tokens emitted each week, shared between all staked animals,
+    /// until the first breakpoint of the emission schedule
     pub base_weekly_emissions: u64,
 
     /// The time the staking starts (in seconds since 1970)
@@ -139,18 +420,509 @@ pub struct Jungle {
 
     /// The root of the merkle tree used to know if a token is part of the collection
     pub root: [u8; 32],
+
+    /// The sum of the weights of all staked animals, in basis points
+    pub total_weight: u64,
+
+    /// The rewards earned by a unit of weight since the start, scaled by `REWARD_PRECISION`
+    pub acc_reward_per_weight: u128,
+
+    /// The last time the rewards were accrued
+    pub last_update: i64,
+
+    /// How newly staked animals are held
+    pub staking_mode: StakingMode,
+
+    /// The lock periods stakers can choose from, with their bonus multiplier
+    pub lock_tiers: [LockTier; LOCK_TIERS],
+
+    /// The changes of the weekly emissions, sorted by time
+    pub emission_schedule: [EmissionBreakpoint; EMISSION_BREAKPOINTS],
+
+    /// Incremented each time the parameters deciding the weights of animals change
+    pub epoch: u64,
+
+    /// The wallet proposed as the next owner, until it accepts
+    pub pending_owner: Pubkey,
+
+    /// The wallet allowed to pause the jungle along with the owner
+    pub guardian: Pubkey,
+
+    /// The operations currently paused, as a combination of `PAUSE_*` flags
+    pub paused: u8,
+
+    /// The time between queuing a withdrawal and executing it (in seconds)
+    pub withdrawal_delay: i64,
+
+    /// The maximum amount withdrawn per week, zero if unlimited
+    pub withdrawal_cap: u64,
+
+    /// The number of withdrawals queued so far, used to index the next one
+    pub withdrawals_queued: u64,
+
+    /// The start of the current week of withdrawals
+    pub withdrawal_window_start: i64,
+
+    /// The amount withdrawn since the start of the current week
+    pub withdrawn_in_window: u64,
+
+    /// The rewards accrued to staked animals and not claimed yet
+    pub liabilities: u64,
+
+    /// How the program knows that a token is part of the collection
+    pub eligibility: Eligibility,
+
+    /// The verified collection, or first creator, required by the metadata eligibility modes
+    pub collection: Pubkey,
+
+    /// Incremented each time the Merkle root changes
+    pub root_version: u64,
+
+    /// What happens to pending rewards when an animal is unstaked without paying them
+    pub unstake_policy: UnstakePolicy,
+
+    /// The rewards paid from the surplus to whoever cranks the claim of an animal unclaimed for a week,
+    /// up to `MAX_CRANK_TIP_BPS` of the rewards cranked
+    pub crank_tip: u64,
+
+    /// The animals staked in each faction, indexed by `Faction`
+    pub faction_stats: [FactionStats; FACTIONS],
+
+    /// The animals still weighted with the parameters of a previous epoch
+    /// They keep earning at that weight until they are settled or reweighted
+    pub stale_animals: u64,
+}
+
+impl Jungle {
+    /// Whether any of the given operations is paused
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+
+    /// Starts a new epoch, every staked animal keeping its previous weight until it is settled
+    /// The emissions since the last update must have been accrued with `update_rewards`
+    pub fn new_epoch(&mut self) -> ProgramResult {
+        self.epoch = self.epoch.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.stale_animals = 0;
+        for stats in self.faction_stats.iter() {
+            self.stale_animals = self
+                .stale_animals
+                .checked_add(stats.animals)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// The share of the emissions given to an animal of the given rarity and lock tier, in basis points
+    pub fn weight(&self, rarity: u64, lock_tier: &LockTier) -> Result<u64, ProgramError> {
+        if self.maximum_rarity == 0 {
+            return Err(ErrorCode::InvalidRarity.into());
+        }
+        let rarity = if rarity <= self.maximum_rarity { rarity } else { self.maximum_rarity };
+        let rarity_bonus = (self.maximum_rarity_multiplier as u128)
+            .checked_sub(10000)
+            .ok_or(ErrorCode::MathOverflow)?
+            * rarity as u128
+            / self.maximum_rarity as u128;
+        let lock_multiplier = if lock_tier.multiplier > 10000 { lock_tier.multiplier } else { 10000 };
+        let weight = (10000 + rarity_bonus)
+            .checked_mul(lock_multiplier as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / 10000;
+        to_u64(weight)
+    }
+
+    /// The lock tier at the given index, if it is enabled
+    pub fn lock_tier(&self, index: u8) -> Option<&LockTier> {
+        self.lock_tiers
+            .get(index as usize)
+            .filter(|tier| tier.multiplier >= 10000)
+    }
+
+    /// The rarity and faction of a token: its registered attributes if the mint is registered,
+    /// otherwise the given ones, whose Merkle leaf `verify_leaf` must prove against the root
+    pub fn attributes(
+        &self,
+        registered: Option<AnimalAttributes>,
+        mint: &Pubkey,
+        rarity: u64,
+        faction: Faction,
+        verify_leaf: impl FnOnce([u8; 32], [u8; 32]) -> bool,
+    ) -> Result<(u64, Faction), ProgramError> {
+        if let Some(attributes) = registered {
+            return Ok((attributes.rarity, attributes.faction));
+        }
+        if !verify_leaf(self.root, leaf_node(mint, rarity, faction)) {
+            return Err(ErrorCode::InvalidProof.into());
+        }
+        Ok((rarity, faction))
+    }
+
+    /// Checks that a token can join the jungle, then fills its staking account and counts it in the totals
+    /// The animal must already hold its jungle, mint, staker and mode
+    /// The metadata is only needed by the metadata eligibility modes
+    pub fn stake(
+        &mut self,
+        animal: &mut Animal,
+        leaf: &AnimalLeaf,
+        registered: Option<AnimalAttributes>,
+        verify_leaf: impl FnOnce([u8; 32], [u8; 32]) -> bool,
+        metadata: Option<&AccountInfo>,
+        now: i64,
+    ) -> ProgramResult {
+        if self.is_paused(PAUSE_STAKE) {
+            return Err(ErrorCode::Paused.into());
+        }
+        if self.start > now {
+            return Err(ErrorCode::TooEarly.into());
+        }
+
+        let (rarity, faction) =
+            self.attributes(registered, &animal.mint, leaf.rarity, leaf.faction, verify_leaf)?;
+        match metadata {
+            Some(metadata) => metadata::verify(metadata, &animal.mint, self.eligibility, &self.collection)?,
+            None if self.eligibility != Eligibility::Merkle => {
+                return Err(ErrorCode::InvalidEligibility.into());
+            }
+            None => {}
+        }
+        let tier = *self.lock_tier(leaf.lock_tier).ok_or(ErrorCode::InvalidLockTier)?;
+
+        self.update_rewards(now)?;
+
+        animal.bumps = leaf.bumps.clone();
+        animal.last_claim = now;
+        animal.rarity = rarity;
+        animal.faction = faction;
+        animal.weight = self.weight(rarity, &tier)?;
+        animal.reward_debt = animal.accrued_rewards(self)?;
+        animal.epoch = self.epoch;
+        animal.root_version = self.root_version;
+        animal.lock_tier = leaf.lock_tier;
+        animal.locked_until = now.checked_add(tier.duration).ok_or(ErrorCode::MathOverflow)?;
+
+        self.add_animal(animal)
+    }
+
+    /// The tokens emitted between two times, each period being paid at the rate in force
+    pub fn emissions_between(&self, from: i64, to: i64) -> Result<u128, ProgramError> {
+        let mut emitted: u128 = 0;
+        let mut cursor = from;
+        let mut rate = self.base_weekly_emissions;
+        for breakpoint in self.emission_schedule.iter().filter(|b| b.is_enabled()) {
+            if breakpoint.start >= to {
+                break;
+            }
+            if breakpoint.start > cursor {
+                emitted = emitted
+                    .checked_add(rate as u128 * (breakpoint.start - cursor) as u128)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                cursor = breakpoint.start;
+            }
+            rate = breakpoint.weekly_emissions;
+        }
+        if to > cursor {
+            emitted = emitted
+                .checked_add(rate as u128 * (to - cursor) as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(emitted / WEEK as u128)
+    }
+
+    /// Accrues the emissions since the last update to the staked animals
+    pub fn update_rewards(&mut self, now: i64) -> ProgramResult {
+        // The last update only runs ahead of the clock before the start
+        if now < self.last_update && self.last_update > self.start {
+            return Err(ErrorCode::ClockWentBackwards.into());
+        }
+        let from = if self.last_update > self.start { self.last_update } else { self.start };
+        if now > from && self.total_weight > 0 {
+            let emitted = self.emissions_between(from, now)?;
+            let increase = emitted
+                .checked_mul(REWARD_PRECISION)
+                .ok_or(ErrorCode::MathOverflow)?
+                / self.total_weight as u128;
+            self.acc_reward_per_weight = self
+                .acc_reward_per_weight
+                .checked_add(increase)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let accrued = increase
+                .checked_mul(self.total_weight as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / REWARD_PRECISION;
+            self.liabilities = self
+                .liabilities
+                .checked_add(to_u64(accrued)?)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        if now > self.last_update {
+            self.last_update = now;
+        }
+        Ok(())
+    }
+
+    /// Adds an animal's weight to the totals and counts it as staked
+    pub fn add_animal(&mut self, animal: &Animal) -> ProgramResult {
+        self.animals_staked = self.animals_staked.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.total_weight = self
+            .total_weight
+            .checked_add(animal.weight)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.add_to_faction(animal)
+    }
+
+    /// Counts an animal in the statistics of its faction
+    pub fn add_to_faction(&mut self, animal: &Animal) -> ProgramResult {
+        let stats = &mut self.faction_stats[animal.faction as usize];
+        stats.animals = stats.animals.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        stats.weight = stats.weight.checked_add(animal.weight).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Removes an animal's weight from the totals and stops counting it as staked
+    pub fn remove_animal(&mut self, animal: &Animal) -> ProgramResult {
+        self.animals_staked = self.animals_staked.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+        self.total_weight = self
+            .total_weight
+            .checked_sub(animal.weight)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let stats = &mut self.faction_stats[animal.faction as usize];
+        stats.animals = stats.animals.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+        stats.weight = stats.weight.checked_sub(animal.weight).ok_or(ErrorCode::MathOverflow)?;
+
+        if animal.epoch != self.epoch {
+            self.stale_animals = self.stale_animals.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the weight of a staked animal in the totals
+    pub fn reweight(&mut self, animal: &mut Animal, weight: u64) -> ProgramResult {
+        self.total_weight = self
+            .total_weight
+            .checked_sub(animal.weight)
+            .and_then(|total| total.checked_add(weight))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let stats = &mut self.faction_stats[animal.faction as usize];
+        stats.weight = stats
+            .weight
+            .checked_sub(animal.weight)
+            .and_then(|total| total.checked_add(weight))
+            .ok_or(ErrorCode::MathOverflow)?;
+        animal.weight = weight;
+        Ok(())
+    }
+
+    /// Settles the rewards of an animal, then moves it to the weight of the current epoch
+    /// Returns the rewards earned since the last settlement
+    /// The rewards must have been accrued up to now with `update_rewards`
+    pub fn settle(&mut self, animal: &mut Animal) -> Result<u64, ProgramError> {
+        let rewards = animal.pending_rewards(self)?;
+        if animal.epoch != self.epoch {
+            let lock_tier = self.lock_tiers[animal.lock_tier as usize];
+            let weight = self.weight(animal.rarity, &lock_tier)?;
+            self.reweight(animal, weight)?;
+            animal.epoch = self.epoch;
+            self.stale_animals = self.stale_animals.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+        }
+        animal.reward_debt = animal.accrued_rewards(self)?;
+        animal.unpaid_rewards = 0;
+        // Per animal rounding can leave the liabilities slightly above the sum of the rewards
+        self.liabilities = self.liabilities.saturating_sub(rewards);
+        Ok(rewards)
+    }
+
+    /// Settles the rewards of an animal and takes the amount out of them, the rest staying pending
+    /// The rewards must have been accrued up to now with `update_rewards`
+    pub fn spend(&mut self, animal: &mut Animal, amount: u64) -> ProgramResult {
+        let rest = self
+            .settle(animal)?
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientRewards)?;
+        animal.unpaid_rewards = rest;
+        self.liabilities = self.liabilities.checked_add(rest).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Settles the rewards of an animal leaving the jungle if they can be paid from the vault,
+    /// whose balance is given when the staker passed the accounts to pay them
+    /// Otherwise forfeits them, or refuses to under the reject policy if the staker could have been paid,
+    /// so that the policy never keeps an animal from leaving while claims are paused or the vault is short
+    /// Returns the rewards to pay
+    pub fn settle_unstake(
+        &mut self,
+        animal: &mut Animal,
+        vault: Option<u64>,
+    ) -> Result<u64, ProgramError> {
+        let pending = animal.pending_rewards(self)?;
+        let paused = self.is_paused(PAUSE_CLAIM);
+        match vault {
+            Some(balance) if !paused && pending <= balance => return self.settle(animal),
+            None if !paused && pending > 0 && self.unstake_policy == UnstakePolicy::Reject => {
+                return Err(ErrorCode::RewardsNotSettled.into());
+            }
+            _ => {}
+        }
+        self.forfeit(animal)?;
+        Ok(0)
+    }
+
+    /// Drops the unclaimed rewards of an animal leaving the jungle from the liabilities
+    /// The rewards must have been accrued up to now with `update_rewards`
+    pub fn forfeit(&mut self, animal: &Animal) -> ProgramResult {
+        self.liabilities = self.liabilities.saturating_sub(animal.pending_rewards(self)?);
+        Ok(())
+    }
+
+    /// The rewards that are not owed to stakers
+    pub fn surplus(&self, balance: u64) -> u64 {
+        balance.saturating_sub(self.liabilities)
+    }
+
+    /// The amount that can still leave the vault in the current week of withdrawals
+    pub fn withdrawal_allowance(&self, now: i64) -> u64 {
+        if self.withdrawal_cap == 0 {
+            return u64::MAX;
+        }
+        if now >= self.withdrawal_window_start.saturating_add(WEEK) {
+            return self.withdrawal_cap;
+        }
+        self.withdrawal_cap.saturating_sub(self.withdrawn_in_window)
+    }
+
+    /// Counts an amount leaving the vault in the current week of withdrawals, up to the weekly cap
+    pub fn record_withdrawal(&mut self, now: i64, amount: u64) -> ProgramResult {
+        if now >= self.withdrawal_window_start.saturating_add(WEEK) {
+            self.withdrawal_window_start = now;
+            self.withdrawn_in_window = 0;
+        }
+        self.withdrawn_in_window = self
+            .withdrawn_in_window
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.withdrawal_cap != 0 && self.withdrawn_in_window > self.withdrawal_cap {
+            return Err(ErrorCode::WithdrawalCapExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+/// A change of the weekly emissions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct EmissionBreakpoint {
+    /// The time from which the rate applies, null if the breakpoint is unused
+    pub start: i64,
+
+    /// The amount of tokens emitted each week from the start
+    pub weekly_emissions: u64,
+}
+
+impl EmissionBreakpoint {
+    pub fn is_enabled(&self) -> bool {
+        self.start != 0
+    }
+}
+
+/// A lock period stakers can choose when staking
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LockTier {
+    /// The time during which the animal can't be unstaked, in seconds
+    pub duration: i64,
+
+    /// The bonus multiplier stacked on the rarity multiplier, in basis points
+    /// A tier with a null multiplier is disabled
+    pub multiplier: u64,
+}
+
+/// How a staked token is held
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum StakingMode {
+    /// The token is moved to a deposit account owned by the escrow
+    Deposit,
+
+    /// The token stays in the staker's wallet, frozen by the escrow as delegate through Metaplex
+    Freeze,
+
+    /// The token stays in the staker's wallet, frozen by the escrow as freeze authority of the mint
+    FreezeAuthority,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for StakingMode {
+    fn default() -> Self {
+        StakingMode::Deposit
+    }
+}
+
+/// How the program knows that a token is part of the collection
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum Eligibility {
+    /// The mint is a leaf of the Merkle tree
+    Merkle,
+
+    /// The Metaplex metadata has the jungle's collection, verified
+    VerifiedCollection,
+
+    /// The first creator in the Metaplex metadata is the jungle's collection key, verified
+    VerifiedCreator,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Eligibility {
+    fn default() -> Self {
+        Eligibility::Merkle
+    }
+}
+
+/// What happens to the pending rewards of an animal unstaked without the accounts to pay them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum UnstakePolicy {
+    /// The pending rewards are lost
+    Forfeit,
+
+    /// The unstake fails while the animal has pending rewards
+    Reject,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for UnstakePolicy {
+    fn default() -> Self {
+        UnstakePolicy::Forfeit
+    }
+}
+
+/// The faction an animal belongs to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum Faction {
+    None = 0,
+    Sarengti = 1,
+    Amphibian = 2,
+    Reptile = 3,
+    Misfit = 4,
+    Bird = 5,
+    Monkey = 6,
+    Carnivore = 7,
+    Extinct = 8,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Faction {
+    fn default() -> Self {
+        Faction::None
+    }
 }
 
-// Jungle factions:
-//     None = 0,
-//     Sarengti = 1,
-//     Amphibian = 2,
-//     Reptile = 3,
-//     Misfit = 4,
-//     Bird = 5,
-//     Monkey = 6,
-//     Carnivore = 7,
-//     Extinct = 8,
+/// The animals staked in a faction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct FactionStats {
+    /// The number of staked animals
+    pub animals: u64,
+
+    /// The sum of the weights of the staked animals, in basis points
+    pub weight: u64,
+}
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct StakeAnimalBumps {
@@ -158,6 +930,176 @@ pub struct StakeAnimalBumps {
     pub deposit: u8,
 }
 
+/// The Merkle leaf of an animal being staked, with the lock tier chosen for it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AnimalLeaf {
+    /// Bumps used to create the animal PDAs
+    pub bumps: StakeAnimalBumps,
+
+    /// The index of the leaf in the Merkle tree
+    pub index: u32,
+
+    /// How rare the animal is
+    pub rarity: u64,
+
+    /// The faction of the animal
+    pub faction: Faction,
+
+    /// The lock tier chosen for the animal
+    pub lock_tier: u8,
+}
+
+/// The attributes of a mint registered with `register_attributes`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AttributesEntry {
+    /// The mint of the NFT
+    pub mint: Pubkey,
+
+    /// Bump used to create the attributes PDA
+    pub bump: u8,
+
+    /// How rare the animal is
+    pub rarity: u64,
+
+    /// The faction of the animal
+    pub faction: Faction,
+}
+
+/// A withdrawal from the rewards account waiting for its timelock
+#[account]
+#[derive(Default)]
+pub struct Withdrawal {
+    /// The bump used to generate the PDA
+    pub bump: u8,
+
+    /// The index of this withdrawal
+    pub index: u64,
+
+    /// The amount of rewards withdrawn
+    pub amount: u64,
+
+    /// The account receiving the rewards
+    pub destination: Pubkey,
+
+    /// The time the withdrawal was queued, the jungle's delay running from it
+    pub queued_at: i64,
+}
+
+/// The attributes of a mint, registered by the owner of the jungle
+#[account]
+#[derive(Default)]
+pub struct AnimalAttributes {
+    /// Bump used to create this PDA
+    pub bump: u8,
+
+    /// The mint of the NFT
+    pub mint: Pubkey,
+
+    /// How rare the animal is
+    pub rarity: u64,
+
+    /// The faction of the animal
+    pub faction: Faction,
+}
+
+impl AnimalAttributes {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 1;
+
+    /// Reads the registered attributes of the mint, or None if they were never registered
+    /// The account must be the attributes PDA of the mint, so the registry can't be skipped
+    pub fn load<'info>(
+        info: &AccountInfo<'info>,
+        jungle_key: &Pubkey,
+        mint: &Pubkey,
+        program_id: &Pubkey,
+    ) -> std::result::Result<Option<Self>, ProgramError> {
+        let (address, _) = Pubkey::find_program_address(
+            &[b"attributes", jungle_key.as_ref(), mint.as_ref()],
+            program_id,
+        );
+        if address != info.key() {
+            return Err(ErrorCode::InvalidAttributes.into());
+        }
+        if info.owner != program_id {
+            return Ok(None);
+        }
+        let attributes: Account<AnimalAttributes> = Account::try_from(info)?;
+        if attributes.mint != *mint {
+            return Err(ErrorCode::InvalidAttributes.into());
+        }
+        Ok(Some(attributes.into_inner()))
+    }
+}
+
+/// The claim settings of a staker in a jungle
+#[account]
+#[derive(Default)]
+pub struct StakerProfile {
+    /// Bump used to create this PDA
+    pub bump: u8,
+
+    /// The identifier of the jungle the profile applies to, as used in its seeds
+    pub jungle: Pubkey,
+
+    /// The owner of the animals
+    pub staker: Pubkey,
+
+    /// The wallet allowed to claim the staker's rewards, null if none
+    pub delegate: Pubkey,
+
+    /// The token account claimed rewards can be sent to besides the staker's, null if none
+    pub recipient: Pubkey,
+}
+
+impl StakerProfile {
+    /// Reads the profile of the staker, or None if the account is not the staker's profile
+    pub fn load<'info>(
+        info: &AccountInfo<'info>,
+        jungle_key: &Pubkey,
+        staker: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<Option<Self>, ProgramError> {
+        if info.owner != program_id {
+            return Ok(None);
+        }
+        let profile: Account<StakerProfile> = Account::try_from(info)?;
+        let address = Pubkey::create_program_address(
+            &[b"profile", jungle_key.as_ref(), staker.as_ref(), &[profile.bump]],
+            program_id,
+        )?;
+        if address != info.key() || profile.staker != *staker {
+            return Ok(None);
+        }
+        Ok(Some(profile.into_inner()))
+    }
+
+    /// Checks that the authority can claim the staker's rewards to the destination,
+    /// which must belong to the staker or be the recipient of the profile
+    pub fn authorize_claim(
+        profile: Option<&Self>,
+        staker: &Pubkey,
+        authority: &Pubkey,
+        destination: &Pubkey,
+        destination_owner: &Pubkey,
+    ) -> ProgramResult {
+        let delegated = matches!(
+            profile,
+            Some(p) if p.delegate != Pubkey::default() && p.delegate == *authority
+        );
+        if authority != staker && !delegated {
+            return Err(ErrorCode::InvalidDelegate.into());
+        }
+        let to_recipient = matches!(
+            profile,
+            Some(p) if p.recipient != Pubkey::default() && p.recipient == *destination
+        );
+        if destination_owner != staker && !to_recipient {
+            return Err(ErrorCode::InvalidRecipient.into());
+        }
+        Ok(())
+    }
+}
+
 /// The staking account linked to the NFT
 #[account]
 #[derive(Default)]
@@ -174,13 +1116,58 @@ pub struct Animal {
     /// How rare the animal is
     pub rarity: u64,
 
-    /// The wallet to which fees are given
-    pub faction: u8,
+    /// The faction of the animal
+    pub faction: Faction,
 
     /// Last time the owner claimed rewards
     pub last_claim: i64,
+
+    /// The share of the emissions given to the animal, in basis points
+    pub weight: u64,
+
+    /// The rewards already accounted for when the animal was staked or last claimed
+    pub reward_debt: u128,
+
+    /// How the token is held
+    pub mode: StakingMode,
+
+    /// The lock tier chosen when staking
+    pub lock_tier: u8,
+
+    /// The time before which the animal can't be unstaked
+    pub locked_until: i64,
+
+    /// The epoch of the jungle parameters the weight was computed with
+    pub epoch: u64,
+
+    /// The jungle the animal is staked in
+    pub jungle: Pubkey,
+
+    /// The version of the Merkle root the rarity and faction were read from
+    pub root_version: u64,
+
+    /// The rewards owed on top of the accumulator, left pending when spending or not paid at migration
+    pub unpaid_rewards: u64,
 }
 
 impl Animal {
-    pub const LEN: usize = 8 + 2 + 40 + 40 + 8 + 1 + 8;
+    pub const LEN: usize = 8 + 2 + 40 + 40 + 8 + 1 + 8 + 8 + 16 + 1 + 1 + 8 + 8 + 32 + 8 + 8;
+
+    /// The rewards accounted for the animal at the jungle's current accumulator
+    pub fn accrued_rewards(&self, jungle: &Jungle) -> Result<u128, ProgramError> {
+        let accrued = (self.weight as u128)
+            .checked_mul(jungle.acc_reward_per_weight)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(accrued / REWARD_PRECISION)
+    }
+
+    /// The rewards earned by the animal that were not claimed yet
+    pub fn pending_rewards(&self, jungle: &Jungle) -> Result<u64, ProgramError> {
+        let pending = self
+            .accrued_rewards(jungle)?
+            .checked_sub(self.reward_debt)
+            .and_then(|pending| pending.checked_add(self.unpaid_rewards as u128))
+            .ok_or(ErrorCode::MathOverflow)?;
+        to_u64(pending)
+    }
 }
@@ -22,3 +22,65 @@ pub fn verify(proof: Vec<[u8; 32]>, root: [u8; 32], leaf: [u8; 32]) -> bool {
     // Check if the computed hash (root) is equal to the provided root
     computed_hash == root
 }
+
+/// Returns true if all the `leaves` can be proved to be part of a Merkle tree
+/// of `leaf_count` leaves defined by `root`. Each leaf comes with its index in
+/// the tree. The `proof` only contains the hashes that can't be computed from
+/// the leaves, layer by layer and from left to right, so the branches shared
+/// by several leaves are sent once. A node without sibling is promoted to the
+/// next layer as is, like in the tree built by the scripts.
+pub fn verify_multi(
+    proof: Vec<[u8; 32]>,
+    root: [u8; 32],
+    mut leaves: Vec<(u32, [u8; 32])>,
+    leaf_count: u32,
+) -> bool {
+    leaves.sort_by_key(|(index, _)| *index);
+    match leaves.last() {
+        Some((index, _)) if *index < leaf_count => {}
+        _ => return false,
+    }
+    if leaves.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return false;
+    }
+
+    let mut proof = proof.into_iter();
+    let mut layer = leaves;
+    let mut width = leaf_count;
+    while width > 1 {
+        let mut parents = Vec::with_capacity(layer.len());
+        let mut i = 0;
+        while i < layer.len() {
+            let (index, hash) = layer[i];
+            let sibling = index ^ 1;
+            let parent = if i + 1 < layer.len() && layer[i + 1].0 == sibling {
+                // Both children are known
+                i += 1;
+                hash_pair(hash, layer[i].1)
+            } else if sibling < width {
+                match proof.next() {
+                    Some(proof_element) => hash_pair(hash, proof_element),
+                    None => return false,
+                }
+            } else {
+                hash
+            };
+            parents.push((index / 2, parent));
+            i += 1;
+        }
+        layer = parents;
+        width = width / 2 + width % 2;
+    }
+
+    // Every element of the proof must have been used
+    proof.next().is_none() && layer[0].1 == root
+}
+
+/// Hashes two nodes of the tree, sorted
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    if a <= b {
+        solana_program::keccak::hashv(&[&[0x01], &a, &b]).0
+    } else {
+        solana_program::keccak::hashv(&[&[0x01], &b, &a]).0
+    }
+}
@@ -0,0 +1,88 @@
+//! Checks the collection of a token with its Metaplex metadata account, as an alternative
+//! to listing every mint in the Merkle tree.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::freeze::token_metadata;
+use crate::Eligibility;
+
+/// A creator of the token, as stored by Metaplex
+#[derive(AnchorDeserialize)]
+struct Creator {
+    address: Pubkey,
+    verified: bool,
+    #[allow(dead_code)]
+    share: u8,
+}
+
+/// The collection of the token, as stored by Metaplex
+#[derive(AnchorDeserialize)]
+struct Collection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// The start of the Metaplex metadata account, up to the collection
+#[allow(dead_code)]
+#[derive(AnchorDeserialize)]
+struct Metadata {
+    key: u8,
+    update_authority: Pubkey,
+    mint: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<Creator>>,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    edition_nonce: Option<u8>,
+    token_standard: Option<u8>,
+    collection: Option<Collection>,
+}
+
+/// Checks that the metadata of the mint has the verified collection, or first creator,
+/// required by the eligibility mode
+pub fn verify(
+    metadata: &AccountInfo,
+    mint: &Pubkey,
+    eligibility: Eligibility,
+    collection: &Pubkey,
+) -> ProgramResult {
+    if eligibility == Eligibility::Merkle {
+        return Ok(());
+    }
+
+    let (address, _) = Pubkey::find_program_address(
+        &[b"metadata", token_metadata::ID.as_ref(), mint.as_ref()],
+        &token_metadata::ID,
+    );
+    if *metadata.owner != token_metadata::ID || metadata.key() != address {
+        return Err(ErrorCode::InvalidCollection.into());
+    }
+
+    let data = metadata.try_borrow_data()?;
+    let metadata = Metadata::deserialize(&mut &data[..])
+        .map_err(|_| ErrorCode::InvalidCollection)?;
+    if metadata.mint != *mint {
+        return Err(ErrorCode::InvalidCollection.into());
+    }
+
+    let verified = match eligibility {
+        Eligibility::VerifiedCollection => matches!(
+            metadata.collection,
+            Some(Collection { verified: true, key }) if key == *collection
+        ),
+        Eligibility::VerifiedCreator => matches!(
+            metadata.creators.as_deref().and_then(|creators| creators.first()),
+            Some(Creator { verified: true, address, .. }) if address == collection
+        ),
+        Eligibility::Merkle => true,
+    };
+    if !verified {
+        return Err(ErrorCode::InvalidCollection.into());
+    }
+
+    Ok(())
+}
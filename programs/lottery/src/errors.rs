@@ -10,4 +10,49 @@ pub enum ErrorCode {
     
     #[msg("The round is not finished yet")]
     RoundNotFinished,
+
+    #[msg("The round already has a commitment")]
+    AlreadyCommitted,
+
+    #[msg("Players can't enter the round before its secret is committed")]
+    RoundNotCommitted,
+
+    #[msg("The revealed secret does not match the commitment")]
+    InvalidReveal,
+
+    #[msg("Too late to reveal the secret of this round")]
+    RevealTooLate,
+
+    #[msg("The round is waiting for its secret to be revealed")]
+    AwaitingReveal,
+
+    #[msg("A draw is waiting for the oracle's randomness")]
+    DrawPending,
+
+    #[msg("The randomness account does not belong to the lottery's oracle")]
+    InvalidOracle,
+
+    #[msg("The randomness request was already fulfilled")]
+    RandomnessAlreadyFulfilled,
+
+    #[msg("The oracle has not fulfilled the randomness request yet")]
+    RandomnessNotFulfilled,
+
+    #[msg("The round is not being drawn")]
+    RoundNotDrawing,
+
+    #[msg("The winner of the round has not been drawn yet")]
+    DrawNotFulfilled,
+
+    #[msg("The amounts overflow")]
+    MathOverflow,
+
+    #[msg("The account is not the expected account of the lottery")]
+    InvalidAccounts,
+
+    #[msg("The account already has the current layout")]
+    AlreadyMigrated,
+
+    #[msg("The signer is not the owner of the lottery")]
+    InvalidOwner,
 }
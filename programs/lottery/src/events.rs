@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::RoundStatus;
+
+/// A lottery was created
+#[event]
+pub struct LotteryInitialized {
+    pub lottery: Pubkey,
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub period: u64,
+    pub start: i64,
+    pub reveal_window: u64,
+}
+
+/// The parameters of a lottery were changed
+#[event]
+pub struct LotterySet {
+    pub lottery: Pubkey,
+    pub old_start: i64,
+    pub new_start: i64,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+    pub old_period: u64,
+    pub new_period: u64,
+    pub old_reveal_window: u64,
+    pub new_reveal_window: u64,
+    pub old_oracle: Pubkey,
+    pub new_oracle: Pubkey,
+}
+
+/// A new owner was proposed
+#[event]
+pub struct OwnerProposed {
+    pub lottery: Pubkey,
+    pub owner: Pubkey,
+    pub old_pending_owner: Pubkey,
+    pub new_pending_owner: Pubkey,
+}
+
+/// The proposed owner accepted the ownership
+#[event]
+pub struct OwnershipAccepted {
+    pub lottery: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+/// The proposal of a new owner was cancelled
+#[event]
+pub struct OwnerProposalCancelled {
+    pub lottery: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+/// The owner committed to the secret drawing a round
+#[event]
+pub struct RoundCommitted {
+    pub lottery: Pubkey,
+    pub index: u64,
+    pub commitment: [u8; 32],
+}
+
+/// A round opened to players
+#[event]
+pub struct RoundStarted {
+    pub lottery: Pubkey,
+    pub index: u64,
+    pub start: i64,
+    pub pot: u64,
+}
+
+/// A closed round waits for the randomness of the oracle
+#[event]
+pub struct DrawRequested {
+    pub lottery: Pubkey,
+    pub index: u64,
+    pub randomness: Pubkey,
+}
+
+/// The winning faction of a round was drawn, none if the round was never revealed
+#[event]
+pub struct WinnerDrawn {
+    pub lottery: Pubkey,
+    pub index: u64,
+    /// The winning faction, starting at 1, or 0 for no winner
+    pub winner: u8,
+    pub pot: u64,
+}
+
+/// A player spent tokens on factions of a round
+#[event]
+pub struct Participated {
+    pub lottery: Pubkey,
+    pub index: u64,
+    pub player: Pubkey,
+    /// The amounts added by this instruction
+    pub spendings: [u64; 8],
+    pub amount: u64,
+    pub ts: i64,
+}
+
+/// A player collected their share of a round's pot
+#[event]
+pub struct ParticipationClaimed {
+    pub lottery: Pubkey,
+    pub index: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub ts: i64,
+}
+
+/// A lottery was grown to the current layout
+#[event]
+pub struct LotteryMigrated {
+    pub lottery: Pubkey,
+    pub key: Pubkey,
+    pub last_round: u64,
+}
+
+/// A round was grown to the current layout
+#[event]
+pub struct RoundMigrated {
+    pub lottery: Pubkey,
+    pub index: u64,
+    pub status: RoundStatus,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::events::OwnershipAccepted;
+use crate::Lottery;
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    /// The lottery
+    #[account(
+        mut,
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+        has_one = pending_owner
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The wallet proposed as the next owner
+    pub pending_owner: Signer<'info>,
+}
+
+/// Transfers the ownership to the proposed owner
+pub fn handler(ctx: Context<AcceptOwnership>) -> ProgramResult {
+    let lottery = &mut ctx.accounts.lottery;
+    emit!(OwnershipAccepted {
+        lottery: lottery.key(),
+        old_owner: lottery.owner,
+        new_owner: lottery.pending_owner,
+    });
+
+    lottery.owner = lottery.pending_owner;
+    lottery.pending_owner = Pubkey::default();
+
+    msg!("Ownership accepted");
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::events::OwnerProposalCancelled;
+use crate::Lottery;
+
+#[derive(Accounts)]
+pub struct CancelOwnerProposal<'info> {
+    /// The lottery
+    #[account(
+        mut,
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+        has_one = owner
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The wallet that owns the lottery
+    pub owner: Signer<'info>,
+}
+
+/// Clears the pending owner
+pub fn handler(ctx: Context<CancelOwnerProposal>) -> ProgramResult {
+    let lottery = &mut ctx.accounts.lottery;
+    emit!(OwnerProposalCancelled {
+        lottery: lottery.key(),
+        pending_owner: lottery.pending_owner,
+    });
+
+    lottery.pending_owner = Pubkey::default();
+
+    msg!("Owner proposal cancelled");
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::*;
-use crate::{Lottery, LotteryParticipation, LotteryRound};
+use crate::events::ParticipationClaimed;
+use crate::{Lottery, LotteryParticipation, LotteryRound, RoundStatus};
 
 #[derive(Accounts)]
 pub struct ClaimParticipation<'info> {
@@ -69,17 +70,25 @@ pub fn handler(ctx: Context<ClaimParticipation>) -> ProgramResult {
     let lottery = &mut ctx.accounts.lottery;
     let lottery_round = &mut ctx.accounts.lottery_round;
     if ctx.accounts.clock.unix_timestamp <= lottery_round.start + lottery.period as i64
-        && lottery.last_round > lottery_round.index
+        || lottery.last_round <= lottery_round.index
     {
         return Err(ErrorCode::RoundNotFinished.into());
     }
+    if lottery_round.status != RoundStatus::Drawn {
+        return Err(ErrorCode::DrawNotFulfilled.into());
+    }
 
+    // Rounds that were never revealed have no winner and pay nothing
     let participation = &ctx.accounts.participation;
-    let index_winner = (lottery_round.winner - 1) as usize;
-    let amount = if lottery_round.spendings[index_winner] != 0 { 
-        lottery_round.pot * participation.spendings[index_winner] / lottery_round.spendings[index_winner]
-    } else {
+    let amount = if lottery_round.winner == 0 {
         0
+    } else {
+        let index_winner = (lottery_round.winner - 1) as usize;
+        if lottery_round.spendings[index_winner] != 0 { 
+            lottery_round.pot * participation.spendings[index_winner] / lottery_round.spendings[index_winner]
+        } else {
+            0
+        }
     };
 
     // Cap the amount to the balance of the pot
@@ -112,6 +121,14 @@ pub fn handler(ctx: Context<ClaimParticipation>) -> ProgramResult {
         signer,
     )?;
 
+    emit!(ParticipationClaimed {
+        lottery: lottery.key(),
+        index: lottery_round.index,
+        player: ctx.accounts.player.key(),
+        amount,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
+
     msg!("Participation updated");
 
     Ok(())
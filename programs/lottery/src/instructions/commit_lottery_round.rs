@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::events::RoundCommitted;
+use crate::{Lottery, LotteryRound};
+
+#[derive(Accounts)]
+pub struct CommitLotteryRound<'info> {
+    /// The lottery
+    #[account(
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+        has_one = owner
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The current lottery round
+    #[account(
+        mut,
+        seeds = [
+            b"round",
+            lottery.key.as_ref(),
+            lottery.last_round.to_le_bytes().as_ref()
+        ],
+        bump = lottery_round.bump,
+    )]
+    pub lottery_round: Account<'info, LotteryRound>,
+
+    /// The owner of the lottery
+    pub owner: Signer<'info>,
+}
+
+/// Commits the hash of the secret that will be revealed to draw the round
+pub fn handler(ctx: Context<CommitLotteryRound>, commitment: [u8; 32]) -> ProgramResult {
+    let lottery_round = &mut ctx.accounts.lottery_round;
+    if lottery_round.commitment != [0; 32] {
+        return Err(ErrorCode::AlreadyCommitted.into());
+    }
+
+    lottery_round.commitment = commitment;
+
+    emit!(RoundCommitted {
+        lottery: ctx.accounts.lottery.key(),
+        index: lottery_round.index,
+        commitment,
+    });
+
+    msg!("Round committed");
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::events::WinnerDrawn;
+use crate::oracle;
+use crate::{Lottery, LotteryRound, RoundStatus};
+
+#[derive(Accounts)]
+pub struct FulfilLotteryDraw<'info> {
+    /// The lottery
+    #[account(
+        mut,
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The lottery round being drawn
+    #[account(
+        mut,
+        seeds = [
+            b"round",
+            lottery.key.as_ref(),
+            lottery_round.index.to_le_bytes().as_ref()
+        ],
+        bump = lottery_round.bump,
+    )]
+    pub lottery_round: Account<'info, LotteryRound>,
+
+    /// The round open since the draw was requested, receiving the pot when nobody won
+    #[account(
+        mut,
+        seeds = [
+            b"round",
+            lottery.key.as_ref(),
+            lottery.last_round.to_le_bytes().as_ref()
+        ],
+        bump = open_lottery_round.bump,
+    )]
+    pub open_lottery_round: Account<'info, LotteryRound>,
+
+    /// The oracle account holding the randomness
+    #[account(address = lottery.pending_request)]
+    pub randomness: AccountInfo<'info>,
+}
+
+/// Draws the winner of a round once the oracle fulfilled the randomness request
+pub fn handler(ctx: Context<FulfilLotteryDraw>) -> ProgramResult {
+    let lottery = &mut ctx.accounts.lottery;
+    let lottery_round = &mut ctx.accounts.lottery_round;
+    if lottery_round.status != RoundStatus::Drawing {
+        return Err(ErrorCode::RoundNotDrawing.into());
+    }
+
+    let result = oracle::read_randomness(&ctx.accounts.randomness, &lottery.oracle)?;
+    if !result.fulfilled {
+        return Err(ErrorCode::RandomnessNotFulfilled.into());
+    }
+
+    lottery_round.draw(&result.randomness);
+    lottery.pending_request = Pubkey::default();
+
+    let pot = lottery_round.pot;
+    lottery_round.roll_pot(&mut ctx.accounts.open_lottery_round)?;
+
+    emit!(WinnerDrawn {
+        lottery: lottery.key(),
+        index: lottery_round.index,
+        winner: lottery_round.winner,
+        pot,
+    });
+
+    msg!("Round drawn");
+
+    Ok(())
+}
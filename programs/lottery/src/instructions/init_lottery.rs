@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::TokenAccount;
 
+use crate::events::LotteryInitialized;
 use crate::{InitializeLotteryBumps, Lottery, LotteryRound};
 
 #[derive(Accounts)]
@@ -66,6 +67,7 @@ pub fn handler(
     bumps: InitializeLotteryBumps,
     period: u64,
     start: i64,
+    reveal_window: u64,
 ) -> ProgramResult {
     let lottery = &mut ctx.accounts.lottery;
     lottery.bumps = bumps;
@@ -76,6 +78,7 @@ pub fn handler(
     lottery.treasury = ctx.accounts.treasury.key();
     lottery.period = period;
     lottery.last_timestamp = start;
+    lottery.reveal_window = reveal_window;
 
     let lottery_round = &mut ctx.accounts.lottery_round;
     lottery_round.bump = bumps.round;
@@ -83,6 +86,17 @@ pub fn handler(
     lottery_round.start = lottery.last_timestamp;
     lottery_round.pot = 0;
 
+    emit!(LotteryInitialized {
+        lottery: lottery.key(),
+        key: lottery.key,
+        owner: lottery.owner,
+        mint: lottery.mint,
+        treasury: lottery.treasury,
+        period,
+        start,
+        reveal_window,
+    });
+
     msg!("Lottery initialized");
 
     Ok(())
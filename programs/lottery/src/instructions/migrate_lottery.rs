@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::Discriminator;
+
+use crate::errors::*;
+use crate::events::LotteryMigrated;
+use crate::{InitializeLotteryBumps, Lottery, DEFAULT_REVEAL_WINDOW};
+
+#[derive(Accounts)]
+pub struct MigrateLottery<'info> {
+    /// The lottery, still in the layout it was initialized with
+    #[account(mut, owner = *program_id)]
+    pub lottery: AccountInfo<'info>,
+
+    /// The owner of the lottery, paying for the larger account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The lottery as initialized before the commit-reveal draws
+#[derive(AnchorDeserialize)]
+struct LegacyLottery {
+    bumps: InitializeLotteryBumps,
+    key: Pubkey,
+    owner: Pubkey,
+    mint: Pubkey,
+    escrow: Pubkey,
+    treasury: Pubkey,
+    period: u64,
+    last_round: u64,
+    last_timestamp: i64,
+    unclaimed_pot: u64,
+}
+
+/// Grows a lottery initialized before the commit-reveal draws to the current layout
+/// Its rounds are drawn from a committed secret, no oracle being set
+pub fn handler(ctx: Context<MigrateLottery>) -> ProgramResult {
+    let info = &ctx.accounts.lottery;
+    let space = 8 + Lottery::default().try_to_vec()?.len();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != Lottery::discriminator() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+        if data.len() >= space {
+            return Err(ErrorCode::AlreadyMigrated.into());
+        }
+        LegacyLottery::deserialize(&mut &data[8..])?
+    };
+
+    let address = Pubkey::create_program_address(
+        &[b"lottery", legacy.key.as_ref(), &[legacy.bumps.lottery]],
+        ctx.program_id,
+    )?;
+    if address != info.key() {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+    if legacy.owner != ctx.accounts.owner.key() {
+        return Err(ErrorCode::InvalidOwner.into());
+    }
+
+    let required = ctx.accounts.rent.minimum_balance(space);
+    if info.lamports() < required {
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.owner.key(),
+                &address,
+                required - info.lamports(),
+            ),
+            &[
+                ctx.accounts.owner.to_account_info(),
+                info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    info.realloc(space, true)?;
+
+    let lottery = Lottery {
+        bumps: legacy.bumps,
+        key: legacy.key,
+        owner: legacy.owner,
+        mint: legacy.mint,
+        escrow: legacy.escrow,
+        treasury: legacy.treasury,
+        period: legacy.period,
+        last_round: legacy.last_round,
+        last_timestamp: legacy.last_timestamp,
+        unclaimed_pot: legacy.unclaimed_pot,
+        reveal_window: DEFAULT_REVEAL_WINDOW,
+        ..Default::default()
+    };
+    let mut data: &mut [u8] = &mut info.try_borrow_mut_data()?;
+    lottery.try_serialize(&mut data)?;
+
+    emit!(LotteryMigrated {
+        lottery: address,
+        key: lottery.key,
+        last_round: lottery.last_round,
+    });
+
+    msg!("Lottery migrated");
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::Discriminator;
+
+use crate::errors::*;
+use crate::events::RoundMigrated;
+use crate::{Lottery, LotteryRound, RoundStatus};
+
+#[derive(Accounts)]
+pub struct MigrateLotteryRound<'info> {
+    /// The lottery, already migrated
+    #[account(
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+        has_one = owner
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The round, still in the layout it was created with
+    #[account(mut, owner = *program_id)]
+    pub lottery_round: AccountInfo<'info>,
+
+    /// The owner of the lottery, paying for the larger account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// A round as created before the commit-reveal draws
+#[derive(AnchorDeserialize)]
+struct LegacyLotteryRound {
+    bump: u8,
+    index: u64,
+    start: i64,
+    spendings: [u64; 8],
+    pot: u64,
+    winner: u8,
+}
+
+/// Grows a round created before the commit-reveal draws to the current layout
+/// Rounds with a winner are drawn, the others are still open and must be committed before players enter
+pub fn handler(ctx: Context<MigrateLotteryRound>) -> ProgramResult {
+    let info = &ctx.accounts.lottery_round;
+    let space = 8 + LotteryRound::default().try_to_vec()?.len();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != LotteryRound::discriminator() {
+            return Err(ErrorCode::InvalidAccounts.into());
+        }
+        if data.len() >= space {
+            return Err(ErrorCode::AlreadyMigrated.into());
+        }
+        LegacyLotteryRound::deserialize(&mut &data[8..])?
+    };
+
+    let lottery = &ctx.accounts.lottery;
+    let address = Pubkey::create_program_address(
+        &[
+            b"round",
+            lottery.key.as_ref(),
+            legacy.index.to_le_bytes().as_ref(),
+            &[legacy.bump],
+        ],
+        ctx.program_id,
+    )?;
+    if address != info.key() {
+        return Err(ErrorCode::InvalidAccounts.into());
+    }
+
+    let required = ctx.accounts.rent.minimum_balance(space);
+    if info.lamports() < required {
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.owner.key(),
+                &address,
+                required - info.lamports(),
+            ),
+            &[
+                ctx.accounts.owner.to_account_info(),
+                info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    info.realloc(space, true)?;
+
+    let lottery_round = LotteryRound {
+        bump: legacy.bump,
+        index: legacy.index,
+        start: legacy.start,
+        spendings: legacy.spendings,
+        pot: legacy.pot,
+        winner: legacy.winner,
+        status: if legacy.winner != 0 { RoundStatus::Drawn } else { RoundStatus::Open },
+        ..Default::default()
+    };
+    let mut data: &mut [u8] = &mut info.try_borrow_mut_data()?;
+    lottery_round.try_serialize(&mut data)?;
+
+    emit!(RoundMigrated {
+        lottery: lottery.key(),
+        index: lottery_round.index,
+        status: lottery_round.status,
+    });
+
+    msg!("Round migrated");
+
+    Ok(())
+}
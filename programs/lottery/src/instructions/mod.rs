@@ -1,13 +1,31 @@
 pub mod init_lottery;
 pub mod set_lottery;
+pub mod propose_owner;
+pub mod accept_ownership;
+pub mod cancel_owner_proposal;
+pub mod commit_lottery_round;
 pub mod new_lottery_round;
+pub mod fulfil_lottery_draw;
 pub mod participate;
 pub mod update_participation;
+pub mod participate_for;
+pub mod update_participation_for;
 pub mod claim_participation;
+pub mod migrate_lottery;
+pub mod migrate_lottery_round;
 
 pub use init_lottery::*;
 pub use set_lottery::*;
+pub use propose_owner::*;
+pub use accept_ownership::*;
+pub use cancel_owner_proposal::*;
+pub use commit_lottery_round::*;
 pub use new_lottery_round::*;
+pub use fulfil_lottery_draw::*;
 pub use participate::*;
 pub use update_participation::*;
-pub use claim_participation::*;
\ No newline at end of file
+pub use participate_for::*;
+pub use update_participation_for::*;
+pub use claim_participation::*;
+pub use migrate_lottery::*;
+pub use migrate_lottery_round::*;
\ No newline at end of file
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{keccak, sysvar};
 
 use crate::errors::*;
-use crate::{Lottery, LotteryRound};
+use crate::events::{DrawRequested, RoundStarted, WinnerDrawn};
+use crate::oracle;
+use crate::{Lottery, LotteryRound, RoundStatus};
 
 #[derive(Accounts)]
 #[instruction(bump: u8)]
@@ -56,6 +59,14 @@ pub struct NewLotteryRound<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// The oracle account that will receive the randomness of the draw
+    /// Unused when the lottery has no oracle
+    pub randomness: AccountInfo<'info>,
+
+    /// Recent slot hashes, mixed with the revealed secret to draw the winner
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+
     /// Clock account used to know the time
     pub clock: Sysvar<'info, Clock>,
 
@@ -63,19 +74,79 @@ pub struct NewLotteryRound<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Mixes the revealed secret with the round spendings and the latest slot hash
+fn round_seed(secret: &[u8; 32], round: &LotteryRound, slot_hashes: &AccountInfo) -> std::result::Result<[u8; 32], ProgramError> {
+    // The sysvar starts with the number of entries, followed by (slot, hash) pairs from the most recent
+    let data = slot_hashes.try_borrow_data()?;
+    let recent_hash = data.get(16..48).ok_or(ProgramError::InvalidAccountData)?;
+
+    let spendings: Vec<u8> = round.spendings.iter().flat_map(|s| s.to_le_bytes()).collect();
+    Ok(keccak::hashv(&[secret, recent_hash, &spendings, &round.index.to_le_bytes()]).0)
+}
+
 /// Starts a new round of lottery
-pub fn handler(ctx: Context<NewLotteryRound>, bump: u8) -> ProgramResult {
+pub fn handler(ctx: Context<NewLotteryRound>, bump: u8, secret: Option<[u8; 32]>) -> ProgramResult {
     let lottery = &mut ctx.accounts.lottery;
-    if ctx.accounts.clock.unix_timestamp < lottery.last_timestamp + lottery.period as i64 {
+    let round_end = lottery.last_timestamp + lottery.period as i64;
+    if ctx.accounts.clock.unix_timestamp < round_end {
         return Err(ErrorCode::TooSoonForNewRound.into());
     }
+    if lottery.pending_request != Pubkey::default() {
+        return Err(ErrorCode::DrawPending.into());
+    }
 
     let old_round = &mut ctx.accounts.old_lottery_round;
-    old_round.winner = (ctx.accounts.clock.unix_timestamp % 8 + 1) as u64 as u8;
+    let reveal_deadline = round_end + lottery.reveal_window as i64;
+    match secret {
+        Some(secret) => {
+            if old_round.commitment == [0; 32] || keccak::hash(&secret).0 != old_round.commitment {
+                return Err(ErrorCode::InvalidReveal.into());
+            }
+            if ctx.accounts.clock.unix_timestamp > reveal_deadline {
+                return Err(ErrorCode::RevealTooLate.into());
+            }
+            old_round.seed = round_seed(&secret, old_round, &ctx.accounts.slot_hashes)?;
+        }
+        None => {
+            // The owner keeps the right to reveal until the end of the window
+            if old_round.commitment != [0; 32] && ctx.accounts.clock.unix_timestamp <= reveal_deadline {
+                return Err(ErrorCode::AwaitingReveal.into());
+            }
+            old_round.seed = round_seed(&[0; 32], old_round, &ctx.accounts.slot_hashes)?;
+        }
+    }
+
+    if lottery.oracle != Pubkey::default() {
+        // The randomness must not be known before the round closes
+        let result = oracle::read_randomness(&ctx.accounts.randomness, &lottery.oracle)?;
+        if result.fulfilled {
+            return Err(ErrorCode::RandomnessAlreadyFulfilled.into());
+        }
+
+        lottery.pending_request = ctx.accounts.randomness.key();
+        old_round.status = RoundStatus::Drawing;
+    } else if secret.is_some() {
+        old_round.draw(&[]);
+    } else {
+        // Without an oracle, rounds that are never revealed have no winner
+        old_round.winner = 0;
+        old_round.status = RoundStatus::Drawn;
+    }
 
-    // When there is no winners, transfers the pot to next round
-    if old_round.spendings[(old_round.winner - 1) as usize] == 0 {
-        lottery.unclaimed_pot += old_round.pot;
+    let pot = old_round.pot;
+    if old_round.status == RoundStatus::Drawn {
+        emit!(WinnerDrawn {
+            lottery: lottery.key(),
+            index: old_round.index,
+            winner: old_round.winner,
+            pot,
+        });
+    } else {
+        emit!(DrawRequested {
+            lottery: lottery.key(),
+            index: old_round.index,
+            randomness: lottery.pending_request,
+        });
     }
 
     lottery.last_round += 1;
@@ -95,6 +166,17 @@ pub fn handler(ctx: Context<NewLotteryRound>, bump: u8) -> ProgramResult {
 
     lottery.unclaimed_pot = ctx.accounts.escrow.try_lamports()?;
 
+    if old_round.status == RoundStatus::Drawn {
+        old_round.roll_pot(lottery_round)?;
+    }
+
+    emit!(RoundStarted {
+        lottery: lottery.key(),
+        index: lottery_round.index,
+        start: lottery_round.start,
+        pot: lottery_round.pot,
+    });
+
     msg!("New round started");
 
     Ok(())
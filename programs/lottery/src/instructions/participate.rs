@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::errors::*;
+use crate::events::Participated;
 use crate::{Lottery, LotteryParticipation, LotteryRound};
 
 #[derive(Accounts)]
@@ -89,24 +89,26 @@ impl<'info> Participate<'info> {
 pub fn handler(ctx: Context<Participate>, bump: u8, spendings: [u64; 8]) -> ProgramResult {
     let lottery = &ctx.accounts.lottery;
     let lottery_round = &mut ctx.accounts.lottery_round;
-    if ctx.accounts.clock.unix_timestamp > lottery_round.start + lottery.period as i64 {
-        return Err(ErrorCode::RoundFinished.into());
-    }
+    lottery_round.check_open(lottery, ctx.accounts.clock.unix_timestamp)?;
 
     let participation = &mut ctx.accounts.participation;
     participation.bump = bump;
     participation.index = lottery_round.index;
     participation.player = ctx.accounts.player.key();
 
-    let mut sum = 0;
-    for i in 0..spendings.len() {
-        sum += spendings[i];
-        lottery_round.spendings[i] += spendings[i];
-        participation.spendings[i] += spendings[i];
-    }
+    let sum = lottery_round.add_spendings(participation, &spendings)?;
 
     token::transfer(ctx.accounts.transfer_context(), sum)?;
 
+    emit!(Participated {
+        lottery: ctx.accounts.lottery.key(),
+        index: ctx.accounts.participation.index,
+        player: ctx.accounts.participation.player,
+        spendings,
+        amount: sum,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
+
     msg!("Lottery entered");
 
     Ok(())
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::events::Participated;
+use crate::{Lottery, LotteryParticipation, LotteryRound};
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct ParticipateFor<'info> {
+    /// The lottery
+    #[account(
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+        has_one = treasury,
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The lottery round
+    #[account(
+        mut,
+        seeds = [
+            b"round",
+            lottery.key.as_ref(),
+            lottery_round.index.to_le_bytes().as_ref()
+        ],
+        bump = lottery_round.bump,
+    )]
+    pub lottery_round: Account<'info, LotteryRound>,
+
+    /// The lottery participation
+    #[account(
+        init,
+        payer = player,
+        seeds = [
+            b"participation",
+            lottery.key.as_ref(),
+            lottery_round.index.to_le_bytes().as_ref(),
+            player.key().as_ref()
+        ],
+        bump = bump,
+    )]
+    pub participation: Account<'info, LotteryParticipation>,
+
+    /// The owner of the token being staked
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// The owner of the spent tokens, a program spending on behalf of the player
+    pub authority: Signer<'info>,
+
+    /// The user account that spends rewards
+    #[account(
+        mut,
+        constraint = 
+            player_account.owner == authority.key() &&
+            player_account.mint == treasury.mint
+    )]
+    pub player_account: Account<'info, TokenAccount>,
+
+    /// The account that will hold the token being staked
+    #[account(mut)]
+    pub treasury: Account<'info, TokenAccount>,
+
+    /// The program for interacting with the token
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// Clock account used to know the time
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ParticipateFor<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.player_account.to_account_info(),
+                to: self.treasury.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+}
+
+/// Spends tokens held by another program on a faction, on behalf of the player
+pub fn handler(ctx: Context<ParticipateFor>, bump: u8, spendings: [u64; 8]) -> ProgramResult {
+    let lottery = &ctx.accounts.lottery;
+    let lottery_round = &mut ctx.accounts.lottery_round;
+    lottery_round.check_open(lottery, ctx.accounts.clock.unix_timestamp)?;
+
+    let participation = &mut ctx.accounts.participation;
+    participation.bump = bump;
+    participation.index = lottery_round.index;
+    participation.player = ctx.accounts.player.key();
+
+    let sum = lottery_round.add_spendings(participation, &spendings)?;
+
+    token::transfer(ctx.accounts.transfer_context(), sum)?;
+
+    emit!(Participated {
+        lottery: ctx.accounts.lottery.key(),
+        index: ctx.accounts.participation.index,
+        player: ctx.accounts.participation.player,
+        spendings,
+        amount: sum,
+        ts: ctx.accounts.clock.unix_timestamp,
+    });
+
+    msg!("Lottery entered");
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::events::OwnerProposed;
+use crate::Lottery;
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    /// The lottery
+    #[account(
+        mut,
+        seeds = [
+            b"lottery",
+            lottery.key.as_ref()
+        ],
+        bump = lottery.bumps.lottery,
+        has_one = owner
+    )]
+    pub lottery: Account<'info, Lottery>,
+
+    /// The wallet that owns the lottery
+    pub owner: Signer<'info>,
+
+    /// The wallet proposed as the next owner
+    pub new_owner: AccountInfo<'info>,
+}
+
+/// Proposes a new owner, replacing any pending proposal
+pub fn handler(ctx: Context<ProposeOwner>) -> ProgramResult {
+    let lottery = &mut ctx.accounts.lottery;
+    emit!(OwnerProposed {
+        lottery: lottery.key(),
+        owner: lottery.owner,
+        old_pending_owner: lottery.pending_owner,
+        new_pending_owner: ctx.accounts.new_owner.key(),
+    });
+
+    lottery.pending_owner = ctx.accounts.new_owner.key();
+
+    msg!("Owner proposed");
+
+    Ok(())
+}
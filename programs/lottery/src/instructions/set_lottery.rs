@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::*;
+use crate::events::LotterySet;
 use crate::Lottery;
 
 #[derive(Accounts)]
@@ -22,20 +24,45 @@ pub struct SetLottery<'info> {
 }
 
 /// Set a lottery
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<SetLottery>,
     start: i64,
-    owner: Pubkey,
     mint: Pubkey,
     treasury: Pubkey,
-    period: i64
+    period: i64,
+    reveal_window: u64,
+    oracle: Pubkey,
 ) -> ProgramResult {
     let lottery = &mut ctx.accounts.lottery;
-    lottery.owner = owner;
+
+    // The pending draw can only be fulfilled by the oracle it was requested to
+    if oracle != lottery.oracle && lottery.pending_request != Pubkey::default() {
+        return Err(ErrorCode::DrawPending.into());
+    }
+
+    emit!(LotterySet {
+        lottery: lottery.key(),
+        old_start: lottery.last_timestamp,
+        new_start: start,
+        old_mint: lottery.mint,
+        new_mint: mint,
+        old_treasury: lottery.treasury,
+        new_treasury: treasury,
+        old_period: lottery.period,
+        new_period: period as u64,
+        old_reveal_window: lottery.reveal_window,
+        new_reveal_window: reveal_window,
+        old_oracle: lottery.oracle,
+        new_oracle: oracle,
+    });
+
     lottery.mint = mint;
     lottery.treasury = treasury;
     lottery.period = period as u64;
     lottery.last_timestamp = start;
+    lottery.reveal_window = reveal_window;
+    lottery.oracle = oracle;
 
     msg!("Set lottery");
 
@@ -1,14 +1,21 @@
 #![cfg_attr(feature = "no-entrypoint", allow(dead_code))]
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod oracle;
 
+use errors::ErrorCode;
 use instructions::*;
 
 declare_id!("6LUNT8JqxCKFw7u1R1PP2wgDTdgYskwGGFK1azVuzJZC");
 
+/// The reveal window given to lotteries migrated from the legacy layout (in seconds)
+pub const DEFAULT_REVEAL_WINDOW: u64 = 86400;
+
 #[program]
 mod lottery {
     use super::*;
@@ -19,28 +26,60 @@ mod lottery {
         bumps: InitializeLotteryBumps,
         period: u64,
         start: i64,
+        reveal_window: u64,
     ) -> ProgramResult {
-        instructions::init_lottery::handler(ctx, bumps, period, start)
+        instructions::init_lottery::handler(ctx, bumps, period, start, reveal_window)
     }
 
     /// Initializes the lottery
+    #[allow(clippy::too_many_arguments)]
     pub fn set_lottery(
         ctx: Context<SetLottery>,
         start: i64,
-        owner: Pubkey,
         mint: Pubkey,
         treasury: Pubkey,
         period: i64,
+        reveal_window: u64,
+        oracle: Pubkey,
+    ) -> ProgramResult {
+        instructions::set_lottery::handler(ctx, start, mint, treasury, period, reveal_window, oracle)
+    }
+
+    /// Proposes a new owner, who has to accept before getting control of the lottery
+    pub fn propose_owner(ctx: Context<ProposeOwner>) -> ProgramResult {
+        instructions::propose_owner::handler(ctx)
+    }
+
+    /// Accepts the ownership of the lottery proposed by the owner
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> ProgramResult {
+        instructions::accept_ownership::handler(ctx)
+    }
+
+    /// Cancels the pending ownership proposal
+    pub fn cancel_owner_proposal(ctx: Context<CancelOwnerProposal>) -> ProgramResult {
+        instructions::cancel_owner_proposal::handler(ctx)
+    }
+
+    /// Commits the hash of the secret used to draw the current round
+    pub fn commit_lottery_round(
+        ctx: Context<CommitLotteryRound>,
+        commitment: [u8; 32]
     ) -> ProgramResult {
-        instructions::set_lottery::handler(ctx, start, owner, mint, treasury, period)
+        instructions::commit_lottery_round::handler(ctx, commitment)
     }
 
-    /// Starts a new round of lottery with available balance
+    /// Starts a new round of lottery with available balance and requests the draw of the previous one
     pub fn new_lottery_round(
         ctx: Context<NewLotteryRound>,
-        bump: u8
+        bump: u8,
+        secret: Option<[u8; 32]>
     ) -> ProgramResult {
-        instructions::new_lottery_round::handler(ctx, bump)
+        instructions::new_lottery_round::handler(ctx, bump, secret)
+    }
+
+    /// Draws the winner of a round with the randomness of the oracle
+    pub fn fulfil_lottery_draw(ctx: Context<FulfilLotteryDraw>) -> ProgramResult {
+        instructions::fulfil_lottery_draw::handler(ctx)
     }
 
     /// Enter the lottery by spending staking rewards
@@ -53,10 +92,30 @@ mod lottery {
         instructions::update_participation::handler(ctx, spendings)
     }
 
+    /// Enter the lottery with tokens owned by a program spending on behalf of the player
+    pub fn participate_for(ctx: Context<ParticipateFor>, bump: u8, spendings: [u64; 8]) -> ProgramResult {
+        instructions::participate_for::handler(ctx, bump, spendings)
+    }
+
+    /// Update an existing participation with tokens owned by a program spending on behalf of the player
+    pub fn update_participation_for(ctx: Context<UpdateParticipationFor>, spendings: [u64; 8]) -> ProgramResult {
+        instructions::update_participation_for::handler(ctx, spendings)
+    }
+
     /// Claim rewards of an expired lottery round
     pub fn claim_participation(ctx: Context<ClaimParticipation>) -> ProgramResult {
         instructions::claim_participation::handler(ctx)
     }
+
+    /// Grows a lottery initialized before the commit-reveal draws to the current layout
+    pub fn migrate_lottery(ctx: Context<MigrateLottery>) -> ProgramResult {
+        instructions::migrate_lottery::handler(ctx)
+    }
+
+    /// Grows a round created before the commit-reveal draws to the current layout
+    pub fn migrate_lottery_round(ctx: Context<MigrateLotteryRound>) -> ProgramResult {
+        instructions::migrate_lottery_round::handler(ctx)
+    }
 }
 
 // Jungle factions:
@@ -110,6 +169,38 @@ pub struct Lottery {
 
     /// The amount of unclaimed SOL
     pub unclaimed_pot: u64,
+
+    /// The time the owner has to reveal a round's secret after it ends (in seconds)
+    pub reveal_window: u64,
+
+    /// The program of the VRF oracle drawing the rounds, none if rounds are drawn immediately
+    pub oracle: Pubkey,
+
+    /// The oracle account that will receive the randomness of the round being drawn
+    pub pending_request: Pubkey,
+
+    /// The wallet proposed as the next owner, until it accepts
+    pub pending_owner: Pubkey,
+}
+
+/// The state of a lottery round
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum RoundStatus {
+    /// Players can enter the round
+    Open,
+
+    /// The round is over and waits for the randomness of the oracle
+    Drawing,
+
+    /// The winner is known and participations can be claimed
+    Drawn,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RoundStatus {
+    fn default() -> Self {
+        RoundStatus::Open
+    }
 }
 
 /// A single round of lottery
@@ -133,6 +224,74 @@ pub struct LotteryRound {
 
     /// The faction that won this round
     pub winner: u8,
+
+    /// The hash of the secret committed by the owner to draw the winner
+    pub commitment: [u8; 32],
+
+    /// Whether the round is open, being drawn or drawn
+    pub status: RoundStatus,
+
+    /// The entropy gathered when the round closed, mixed with the oracle's randomness
+    pub seed: [u8; 32],
+}
+
+impl LotteryRound {
+    /// Draws the winning faction from the round's seed and the given randomness
+    pub fn draw(&mut self, randomness: &[u8]) {
+        let hash = keccak::hashv(&[&self.seed, randomness]);
+
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&hash.0[..8]);
+        self.winner = (u64::from_le_bytes(value) % 8 + 1) as u8;
+        self.status = RoundStatus::Drawn;
+    }
+
+    /// Whether some players spent tokens on the winning faction
+    pub fn has_winners(&self) -> bool {
+        self.winner != 0 && self.spendings[(self.winner - 1) as usize] != 0
+    }
+
+    /// When there is no winners, transfers the pot to the given open round
+    /// Its lamports are already counted in the unclaimed pot, which keeps them for that round
+    pub fn roll_pot(&mut self, open_round: &mut LotteryRound) -> ProgramResult {
+        if !self.has_winners() {
+            open_round.pot = open_round.pot.checked_add(self.pot).ok_or(ErrorCode::MathOverflow)?;
+            self.pot = 0;
+        }
+        Ok(())
+    }
+
+    /// Adds the spendings of a participation to the round, returning the amount spent
+    pub fn add_spendings(
+        &mut self,
+        participation: &mut LotteryParticipation,
+        spendings: &[u64; 8],
+    ) -> std::result::Result<u64, ProgramError> {
+        let mut sum: u64 = 0;
+        for (i, spending) in spendings.iter().enumerate() {
+            sum = sum.checked_add(*spending).ok_or(ErrorCode::MathOverflow)?;
+            self.spendings[i] = self.spendings[i].checked_add(*spending).ok_or(ErrorCode::MathOverflow)?;
+            participation.spendings[i] = participation.spendings[i]
+                .checked_add(*spending)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(sum)
+    }
+
+    /// Checks that players can enter the round, which must be the current one and committed
+    /// A secret committed once spendings are known could be picked to favor a faction
+    pub fn check_open(&self, lottery: &Lottery, now: i64) -> ProgramResult {
+        if self.index != lottery.last_round
+            || self.status != RoundStatus::Open
+            || now >= self.start + lottery.period as i64
+        {
+            return Err(ErrorCode::RoundFinished.into());
+        }
+        if self.commitment == [0; 32] {
+            return Err(ErrorCode::RoundNotCommitted.into());
+        }
+        Ok(())
+    }
 }
 
 /// A user participation in a round of lottery
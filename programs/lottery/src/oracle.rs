@@ -0,0 +1,28 @@
+//! Adapter reading the randomness written by a VRF oracle.
+//! Any oracle program can be plugged in, as long as its result accounts start with
+//! an 8 bytes discriminator followed by the `RandomnessResult` layout.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+
+/// The randomness written by the oracle in its result account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct RandomnessResult {
+    /// True once the oracle wrote the randomness
+    pub fulfilled: bool,
+
+    /// The verified random value
+    pub randomness: [u8; 32],
+}
+
+/// Reads the result of a randomness request, checking it belongs to the oracle
+pub fn read_randomness(account: &AccountInfo, oracle: &Pubkey) -> std::result::Result<RandomnessResult, ProgramError> {
+    if account.owner != oracle {
+        return Err(ErrorCode::InvalidOracle.into());
+    }
+
+    let data = account.try_borrow_data()?;
+    let mut bytes = data.get(8..).ok_or(ProgramError::InvalidAccountData)?;
+    RandomnessResult::deserialize(&mut bytes).map_err(|_| ProgramError::InvalidAccountData)
+}
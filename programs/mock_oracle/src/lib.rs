@@ -0,0 +1,77 @@
+//! A stand-in for a VRF oracle, used to test the lottery draws locally.
+//! The randomness is written by whoever created the request instead of being verified.
+
+#![cfg_attr(feature = "no-entrypoint", allow(dead_code))]
+
+use anchor_lang::prelude::*;
+
+declare_id!("4HNNuHt3JyKTRt5imKZ972osq3gcDySiRvXUcbMpoAS8");
+
+#[program]
+mod mock_oracle {
+    use super::*;
+
+    /// Creates a randomness request
+    pub fn request_randomness(ctx: Context<RequestRandomness>) -> ProgramResult {
+        let result = &mut ctx.accounts.result;
+        result.authority = ctx.accounts.authority.key();
+
+        msg!("Randomness requested");
+
+        Ok(())
+    }
+
+    /// Writes the randomness of a request
+    pub fn fulfil_randomness(ctx: Context<FulfilRandomness>, randomness: [u8; 32]) -> ProgramResult {
+        let result = &mut ctx.accounts.result;
+        result.fulfilled = true;
+        result.randomness = randomness;
+
+        msg!("Randomness fulfilled");
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RequestRandomness<'info> {
+    /// The account receiving the randomness
+    #[account(init, payer = authority)]
+    pub result: Account<'info, RandomnessResult>,
+
+    /// The wallet allowed to fulfil the request
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfilRandomness<'info> {
+    /// The account receiving the randomness
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !result.fulfilled
+    )]
+    pub result: Account<'info, RandomnessResult>,
+
+    /// The wallet allowed to fulfil the request
+    pub authority: Signer<'info>,
+}
+
+/// The result of a randomness request
+/// Starts with the layout expected by the lottery's oracle adapter
+#[account]
+#[derive(Default)]
+pub struct RandomnessResult {
+    /// True once the randomness was written
+    pub fulfilled: bool,
+
+    /// The random value
+    pub randomness: [u8; 32],
+
+    /// The wallet allowed to fulfil the request
+    pub authority: Pubkey,
+}
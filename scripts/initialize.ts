@@ -14,6 +14,7 @@ import {
   PublicKey,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { buildLeaves } from "../tests/helpers";
 import { MerkleTree } from "../tests/helpers/merkleTree";
@@ -104,7 +105,14 @@ const initialize = async (network: string) => {
   const maxRarity = new BN(config.maxRarity);
   const baseWeeklyEmissions = new BN(config.weeklyRewards).mul(new BN(10 ** 9));
   const lotteryPeriod = new BN(config.lotteryPeriod);
+  const revealWindow = new BN(config.revealWindow);
   const start = new BN(config.start);
+  const withdrawalDelay = new BN(config.withdrawalDelay);
+  const stakingMode = { [config.stakingMode]: {} };
+  const lockTiers = config.lockTiers.map(([duration, multiplier]) => ({
+    duration: new BN(duration),
+    multiplier: new BN(multiplier),
+  }));
 
   const leaves = buildLeaves(
     mints.map((e, i) => ({
@@ -153,6 +161,7 @@ const initialize = async (network: string) => {
       baseWeeklyEmissions,
       start,
       tree.getRootArray(),
+      withdrawalDelay,
       {
         accounts: {
           jungleKey: jungleKey,
@@ -177,11 +186,13 @@ const initialize = async (network: string) => {
       baseWeeklyEmissions,
       start,
       tree.getRootArray(),
+      stakingMode,
+      lockTiers,
       {
         accounts: {
           jungle: jungleAddress,
           owner: wallet.payer.publicKey,
-          newOwner: wallet.payer.publicKey,
+          clock: SYSVAR_CLOCK_PUBKEY,
         },
         signers: [wallet.payer],
       }
@@ -237,7 +248,7 @@ const initialize = async (network: string) => {
       round: roundBump,
     };
 
-    await lotteryProgram.rpc.initializeLottery(bumps, lotteryPeriod, start, {
+    await lotteryProgram.rpc.initializeLottery(bumps, lotteryPeriod, start, revealWindow, {
       accounts: {
         lotteryKey: lotteryKey,
         lottery: lotteryAddress,
@@ -254,10 +265,11 @@ const initialize = async (network: string) => {
   } catch (err) {
     await lotteryProgram.rpc.setLottery(
       start,
-      wallet.payer.publicKey,
       mintRewards.publicKey,
       rewards,
       lotteryPeriod,
+      revealWindow,
+      new PublicKey(0),
       {
         accounts: {
           lottery: lotteryAddress,
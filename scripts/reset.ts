@@ -14,6 +14,7 @@ import {
   PublicKey,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { buildLeaves } from "../tests/helpers";
 import { MerkleTree } from "../tests/helpers/merkleTree";
@@ -109,7 +110,13 @@ const reset = async (network: string) => {
   const maxRarity = new BN(config.maxRarity);
   const baseWeeklyEmissions = new BN(config.weeklyRewards).mul(new BN(10 ** 9));
   const lotteryPeriod = new BN(config.lotteryPeriod);
+  const revealWindow = new BN(config.revealWindow);
   const start = new BN(config.start);
+  const stakingMode = { [config.stakingMode]: {} };
+  const lockTiers = config.lockTiers.map(([duration, multiplier]) => ({
+    duration: new BN(duration),
+    multiplier: new BN(multiplier),
+  }));
 
   const leaves = buildLeaves(
     mints.map((e, i) => ({
@@ -150,11 +157,13 @@ const reset = async (network: string) => {
     baseWeeklyEmissions,
     start,
     tree.getRootArray(),
+    stakingMode,
+    lockTiers,
     {
       accounts: {
         jungle: jungleAddress,
         owner: wallet.payer.publicKey,
-        newOwner: wallet.payer.publicKey,
+        clock: SYSVAR_CLOCK_PUBKEY,
       },
       signers: [wallet.payer],
     }
@@ -168,10 +177,11 @@ const reset = async (network: string) => {
 
   await lotteryProgram.rpc.setLottery(
     start,
-    wallet.payer.publicKey,
     mintRewards.publicKey,
     rewards,
     lotteryPeriod,
+    revealWindow,
+    new PublicKey(0),
     {
       accounts: {
         lottery: lotteryAddress,
@@ -6,6 +6,7 @@ import {
   Token,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
+import { keccak_256 } from "js-sha3";
 import { MerkleTree } from "./merkleTree";
 
 export const findAssociatedAddress = async (
@@ -21,6 +22,18 @@ export const findAssociatedAddress = async (
   );
 };
 
+export const findAttributesAddress = async (
+  jungleKey: web3.PublicKey,
+  mint: web3.PublicKey,
+  programId: web3.PublicKey
+) => {
+  const [address] = await web3.PublicKey.findProgramAddress(
+    [Buffer.from("attributes", "utf8"), jungleKey.toBuffer(), mint.toBuffer()],
+    programId
+  );
+  return address;
+};
+
 export const assertFail = async (pendingTx: Promise<any>, error?: string) => {
   const log = console.log;
   console.log = () => {};
@@ -36,6 +49,30 @@ export const assertFail = async (pendingTx: Promise<any>, error?: string) => {
   if (success) throw new Error("Should have failed");
 };
 
+export const generateSecret = () => {
+  const secret = Array.from(web3.Keypair.generate().secretKey.slice(0, 32));
+  const commitment = keccak_256.digest(secret);
+  return { secret, commitment };
+};
+
+// The lock tiers of a jungle, as [duration, multiplier] pairs, the others being disabled
+export const lockTiers = (tiers: number[][] = [[0, 10000]]) =>
+  Array(4)
+    .fill(0)
+    .map((_, i) => ({
+      duration: new BN(i < tiers.length ? tiers[i][0] : 0),
+      multiplier: new BN(i < tiers.length ? tiers[i][1] : 0),
+    }));
+
+// The emission schedule of a jungle, as [start, weekly emissions] pairs, the others being unused
+export const emissionSchedule = (breakpoints: number[][] = []) =>
+  Array(8)
+    .fill(0)
+    .map((_, i) => ({
+      start: new BN(i < breakpoints.length ? breakpoints[i][0] : 0),
+      weeklyEmissions: new BN(i < breakpoints.length ? breakpoints[i][1] : 0),
+    }));
+
 export const airdropUsers = async (
   users: web3.Signer[],
   provider: Provider,
@@ -119,6 +156,22 @@ export const merkleCollection = async (
   };
 };
 
+/** The factions of the jungle, indexed by their id */
+export const FACTIONS = [
+  "none",
+  "sarengti",
+  "amphibian",
+  "reptile",
+  "misfit",
+  "bird",
+  "monkey",
+  "carnivore",
+  "extinct",
+];
+
+/** The `Faction` enum argument of the given id */
+export const faction = (id: number) => ({ [FACTIONS[id]]: {} });
+
 export const buildLeaves = (
   data: { mint: web3.PublicKey; rarity: number; faction: number }[]
 ) => {
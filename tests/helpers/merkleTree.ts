@@ -71,6 +71,32 @@ export class MerkleTree {
     return res;
   }
 
+  // Proof of several leaves at once, without the nodes computed from the leaves
+  getMultiProof(indexes: number[]): Buffer[] {
+    let known = Array.from(new Set(indexes)).sort((a, b) => a - b);
+    const proof = [];
+
+    for (const layer of this.layers.slice(0, -1)) {
+      const parents = [];
+      for (let i = 0; i < known.length; i++) {
+        const sibling = known[i] ^ 1;
+        if (known[i + 1] === sibling) {
+          i++;
+        } else if (sibling < layer.length) {
+          proof.push(layer[sibling]);
+        }
+        parents.push(Math.floor(known[i] / 2));
+      }
+      known = parents;
+    }
+
+    return proof;
+  }
+
+  getMultiProofArray(indexes: number[]) {
+    return this.getMultiProof(indexes).map((e) => Array.from(e.values()));
+  }
+
   getHexRoot(): string {
     return this.getRoot().toString("hex");
   }
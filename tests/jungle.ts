@@ -14,10 +14,25 @@ import {
   mintAndTransferRewards,
 } from "./helpers";
 import { testClaimRewards } from "./suites/claimRewards";
+import { testClaimStakingMany } from "./suites/claimStakingMany";
+import { testClaimDelegate } from "./suites/claimDelegate";
+import { testCrankClaim } from "./suites/crankClaim";
+import { testEnterLottery } from "./suites/enterLottery";
 import { testInitializeJungle } from "./suites/initJungle";
+import { testMigrateAnimal } from "./suites/migrateAnimal";
 import { testSetJungle } from "./suites/setJungle";
+import { testSetEmissionSchedule } from "./suites/setEmissionSchedule";
+import { testTransferOwnership } from "./suites/transferOwnership";
+import { testSetPause } from "./suites/setPause";
 import { testStakeAnimal } from "./suites/stakeAnimal";
 import { testUnstakeAnimal } from "./suites/unstakeAnimal";
+import { testRefreshAnimal } from "./suites/refreshAnimal";
+import { testTransferStake } from "./suites/transferStake";
+import { testStakeAnimalInWallet } from "./suites/stakeAnimalInWallet";
+import { testStakeAnimals } from "./suites/stakeAnimals";
+import { testStakeCollection } from "./suites/stakeCollection";
+import { testRegisterAttributes } from "./suites/registerAttributes";
+import { testUnstakeAnimals } from "./suites/unstakeAnimals";
 import { testWithdrawRewards } from "./suites/withdrawRewards";
 
 describe("jungle", () => {
@@ -65,8 +80,23 @@ describe("jungle", () => {
 
   // testInitializeJungle(state, provider);
   // testSetJungle(state, provider);
+  // testSetEmissionSchedule(state, provider);
+  // testTransferOwnership(state, provider);
+  // testSetPause(state, provider);
   // testWithdrawRewards(state, provider);
   // testStakeAnimal(state, provider);
   // testUnstakeAnimal(state, provider);
+  // testRefreshAnimal(state, provider);
+  // testTransferStake(state, provider);
+  // testStakeAnimalInWallet(state, provider);
+  // testStakeAnimals(state, provider);
+  // testStakeCollection(state, provider);
+  // testRegisterAttributes(state, provider);
+  // testUnstakeAnimals(state, provider);
   // testClaimRewards(state, provider);
+  // testClaimStakingMany(state, provider);
+  // testClaimDelegate(state, provider);
+  // testCrankClaim(state, provider);
+  // testEnterLottery(state, provider);
+  // testMigrateAnimal(provider);
 });
@@ -1,9 +1,13 @@
 import { setProvider, Provider } from "@project-serum/anchor";
 import { testClaimParticipation } from "./suites/claimParticipation";
+import { testCommitLotteryRound } from "./suites/commitLotteryRound";
+import { testFulfilLotteryDraw } from "./suites/fulfilLotteryDraw";
 import { testInitializeLottery } from "./suites/initLottery";
+import { testMigrateLottery } from "./suites/migrateLottery";
 import { testNewLotteryRound } from "./suites/newLotteryRound";
 import { testParticipate } from "./suites/participate";
 import { testSetLottery } from "./suites/setLottery";
+import { testTransferLotteryOwnership } from "./suites/transferLotteryOwnership";
 import { testUpdateParticipation } from "./suites/updateParticipation";
 
 describe("Lottery", () => {
@@ -12,8 +16,12 @@ describe("Lottery", () => {
 
   testInitializeLottery(provider);
   testSetLottery(provider);
+  testTransferLotteryOwnership(provider);
+  testCommitLotteryRound(provider);
   testNewLotteryRound(provider);
+  testFulfilLotteryDraw(provider);
   testParticipate(provider);
   testUpdateParticipation(provider);
   testClaimParticipation(provider);
+  testMigrateLottery(provider);
 });
@@ -0,0 +1,309 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testClaimDelegate = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Claim rewards through a delegate", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 4;
+    const index = 1;
+    let mintRewards: Token, mints: Token[], tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+    let animal: PublicKey;
+
+    const findProfile = () =>
+      PublicKey.findProgramAddress(
+        [
+          Buffer.from("profile", "utf8"),
+          jungleKey.toBuffer(),
+          staker.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    const setClaimDelegate = async (
+      delegate: PublicKey,
+      recipient: PublicKey
+    ) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [profile, profileBump] = await findProfile();
+
+      await program.rpc.initStakerProfile(profileBump, {
+        accounts: {
+          jungle: jungleAddress,
+          profile: profile,
+          staker: staker.publicKey,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [staker],
+      });
+      const p = await program.account.stakerProfile.fetch(profile);
+      expect(p.jungle.toString()).to.equal(jungleKey.toString());
+
+      await program.rpc.setClaimDelegate(delegate, recipient, {
+        accounts: {
+          profile: profile,
+          staker: staker.publicKey,
+        },
+        signers: [staker],
+      });
+    };
+
+    const claimStaking = async (authority: Keypair, destination: PublicKey) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [profile] = await findProfile();
+
+      await program.rpc.claimStaking({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: staker.publicKey,
+          authority: authority.publicKey,
+          profile: profile,
+          mint: mintRewards.publicKey,
+          stakerAccount: destination,
+          rewardsAccount: rewardsAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [authority],
+      });
+    };
+
+    const rewardsAccountOf = async (wallet: PublicKey) =>
+      (await mintRewards.getOrCreateAssociatedAccountInfo(wallet)).address;
+
+    const stakeAnimal = async (index: number) => {
+      const mint = mints[index];
+      const stakerAccount = (
+        await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        faction(index % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mint.publicKey,
+            stakerAccount: stakerAccount,
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mint.publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return animal;
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      tree = nfts.tree;
+      await Promise.all(
+        mints.map(async (mint) => {
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          const stakerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, stakerAccount, owner, [], 1);
+        })
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+      await mintRewards.mintTo(rewards, owner, [], 10 ** 14);
+
+      animal = await stakeAnimal(index);
+    });
+
+    it("The delegate claims to the staker's account", async () => {
+      const keeper = Keypair.generate();
+      await airdropUsers([keeper], provider);
+      await setClaimDelegate(keeper.publicKey, PublicKey.default);
+
+      const destination = await rewardsAccountOf(staker.publicKey);
+      await claimStaking(keeper, destination);
+
+      const rewards = await mintRewards.getAccountInfo(destination);
+      expect(rewards.amount.toNumber()).to.be.above(0);
+    });
+
+    it("The delegate claims to the recipient", async () => {
+      const keeper = Keypair.generate();
+      const cold = Keypair.generate();
+      await airdropUsers([keeper], provider);
+      const recipient = await rewardsAccountOf(cold.publicKey);
+      await setClaimDelegate(keeper.publicKey, recipient);
+
+      await claimStaking(keeper, recipient);
+
+      const rewards = await mintRewards.getAccountInfo(recipient);
+      expect(rewards.amount.toNumber()).to.be.above(0);
+    });
+
+    it("The delegate can't claim to its own account", async () => {
+      const keeper = Keypair.generate();
+      await airdropUsers([keeper], provider);
+      await setClaimDelegate(keeper.publicKey, PublicKey.default);
+
+      await assertFail(
+        claimStaking(keeper, await rewardsAccountOf(keeper.publicKey))
+      );
+    });
+
+    it("Can't claim without being the delegate", async () => {
+      const stranger = Keypair.generate();
+      await airdropUsers([stranger], provider);
+
+      await assertFail(
+        claimStaking(stranger, await rewardsAccountOf(staker.publicKey))
+      );
+    });
+  });
@@ -13,9 +13,10 @@ import {
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
   SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
 } from "@solana/web3.js";
 import { Lottery } from "../../target/types/lottery";
-import { airdropUsers, assertFail } from "../helpers";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 export const testClaimParticipation = (provider: Provider) =>
@@ -27,8 +28,10 @@ export const testClaimParticipation = (provider: Provider) =>
     let owner: Keypair, player: Keypair;
     let mintRewards: Token;
     let lotteryKey: PublicKey, treasury: PublicKey, playerAccount: PublicKey;
+    let secret: { secret: number[]; commitment: number[] };
 
     const startingAmount = new BN(10 ** 10);
+    const revealWindow = new BN(100);
     const period = new BN(3);
 
     beforeEach(async () => {
@@ -83,7 +86,7 @@ export const testClaimParticipation = (provider: Provider) =>
 
       const start = Math.round(Date.now() / 1000) - 6;
 
-      await program.rpc.initializeLottery(bumps, period, new BN(start), {
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
         accounts: {
           lotteryKey: lotteryKey,
           lottery: lotteryAddress,
@@ -121,19 +124,31 @@ export const testClaimParticipation = (provider: Provider) =>
         [owner]
       );
 
-      await program.rpc.newLotteryRound(nextRoundBump, {
+      await program.rpc.newLotteryRound(nextRoundBump, null, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: nextRound,
           oldLotteryRound: round,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
         },
         signers: [player],
       });
+
+      secret = generateSecret();
+      await program.rpc.commitLotteryRound(secret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: nextRound,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
     });
 
     it("Claim the rewards", async () => {
@@ -197,13 +212,15 @@ export const testClaimParticipation = (provider: Provider) =>
       );
 
       // Start the next round
-      await program.rpc.newLotteryRound(nextRoundBump, {
+      await program.rpc.newLotteryRound(nextRoundBump, secret.secret, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: nextRound,
           oldLotteryRound: round,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
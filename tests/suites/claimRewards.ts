@@ -14,7 +14,14 @@ import {
   SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { Jungle } from "../../target/types/jungle";
-import { airdropUsers, assertFail, merkleCollection } from "../helpers";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  lockTiers,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { MerkleTree } from "../helpers/merkleTree";
 
@@ -109,6 +116,7 @@ export const testClaimRewards = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        new BN(1),
         {
           accounts: {
             jungleKey: jungleKey,
@@ -131,6 +139,7 @@ export const testClaimRewards = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -138,6 +147,7 @@ export const testClaimRewards = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -152,7 +162,8 @@ export const testClaimRewards = (
         bumpsAnimal,
         tree.getProofArray(indexStaked),
         new BN(indexStaked),
-        new BN(indexStaked % 8),
+        faction(indexStaked % 8),
+        0,
         {
           accounts: {
             jungle: jungleAddress,
@@ -161,6 +172,12 @@ export const testClaimRewards = (
             staker: holders[indexStaked].publicKey,
             mint: mints[indexStaked].publicKey,
             stakerAccount: accounts[indexStaked],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[indexStaked].publicKey,
+              program.programId
+            ),
             depositAccount: deposit,
             tokenProgram: TOKEN_PROGRAM_ID,
             clock: SYSVAR_CLOCK_PUBKEY,
@@ -174,6 +191,7 @@ export const testClaimRewards = (
       const [otherAnimal, otherAnimalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStakedOther].publicKey.toBuffer(),
         ],
         program.programId
@@ -181,6 +199,7 @@ export const testClaimRewards = (
       const [otherDeposit, otherDepositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStakedOther].publicKey.toBuffer(),
         ],
         program.programId
@@ -195,7 +214,8 @@ export const testClaimRewards = (
         bumpsAnimalOther,
         tree.getProofArray(indexStakedOther),
         new BN(indexStakedOther),
-        new BN(indexStakedOther % 8),
+        faction(indexStakedOther % 8),
+        0,
         {
           accounts: {
             jungle: jungleAddress,
@@ -204,6 +224,12 @@ export const testClaimRewards = (
             staker: holders[indexStakedOther].publicKey,
             mint: mints[indexStakedOther].publicKey,
             stakerAccount: accounts[indexStakedOther],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[indexStakedOther].publicKey,
+              program.programId
+            ),
             depositAccount: otherDeposit,
             tokenProgram: TOKEN_PROGRAM_ID,
             clock: SYSVAR_CLOCK_PUBKEY,
@@ -227,6 +253,7 @@ export const testClaimRewards = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -263,6 +290,8 @@ export const testClaimRewards = (
           escrow: escrow,
           animal: animal,
           staker: holders[indexStaked].publicKey,
+          authority: holders[indexStaked].publicKey,
+          profile: SystemProgram.programId,
           mint: rewardToken.publicKey,
           stakerAccount: stakerAccount.address,
           rewardsAccount: rewardsAccount,
@@ -291,21 +320,245 @@ export const testClaimRewards = (
         stakerAccount.amount.add(rewardsGiven).toString()
       );
 
-      // The amount given is correct
+      // The amount given is the share of the animal in the emissions
+      const precision = new BN(10 ** 12);
+      expect(rewardsGiven.toString()).to.equal(
+        a.weight
+          .mul(j.accRewardPerWeight)
+          .div(precision)
+          .sub(animalBefore.rewardDebt)
+          .toString()
+      );
+      expect(a.rewardDebt.toString()).to.equal(
+        a.weight.mul(j.accRewardPerWeight).div(precision).toString()
+      );
+
+      // The staked animals share the weekly emissions
       const elapsed = a.lastClaim.sub(animalBefore.lastClaim);
-      const rarityMultiplier = new BN(10000).add(
-        state.maxMultiplier
-          .sub(new BN(10000))
-          .mul(new BN(indexStaked))
-          .div(new BN(n - 1))
+      expect(
+        rewardsGiven.lte(
+          state.baseWeeklyEmissions.mul(elapsed).div(new BN(604800))
+        )
+      ).to.equal(true);
+    });
+
+    it("Keeps the rewards earned before the parameters change", async () => {
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
       );
-      expect(rewardsGiven.toString()).to.equal(
-        state.baseWeeklyEmissions
-          .mul(elapsed)
-          .div(new BN(604800))
-          .mul(rarityMultiplier)
-          .div(j.animalsStaked)
-          .div(new BN(10000))
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      const animalBefore = await program.account.animal.fetch(animal);
+      const jungleBefore = await program.account.jungle.fetch(jungleAddress);
+
+      // Stop the emissions and change the weights
+      const newMaxMultiplier = state.maxMultiplier.mul(new BN(2));
+      await program.rpc.setJungle(
+        maxRarity,
+        newMaxMultiplier,
+        new BN(0),
+        state.start,
+        tree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [owner],
+        }
+      );
+      const jungleSet = await program.account.jungle.fetch(jungleAddress);
+      expect(jungleSet.epoch.toString()).to.equal(
+        jungleBefore.epoch.add(new BN(1)).toString()
+      );
+      expect(jungleSet.staleAnimals.toString()).to.equal("2");
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const rewardToken = new Token(
+        provider.connection,
+        jungleBefore.mint,
+        TOKEN_PROGRAM_ID,
+        holders[indexStaked]
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleBefore.key.toBuffer(),
+          jungleBefore.mint.toBuffer(),
+        ],
+        program.programId
+      );
+      const stakerAccount = await rewardToken.getOrCreateAssociatedAccountInfo(
+        holders[indexStaked].publicKey
+      );
+
+      await program.rpc.claimStaking({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: holders[indexStaked].publicKey,
+          authority: holders[indexStaked].publicKey,
+          profile: SystemProgram.programId,
+          mint: rewardToken.publicKey,
+          stakerAccount: stakerAccount.address,
+          rewardsAccount: rewardsAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [holders[indexStaked]],
+      });
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      const a = await program.account.animal.fetch(animal);
+      const stakerAccountAfter = await rewardToken.getAccountInfo(
+        stakerAccount.address
+      );
+
+      // Paid at the old weight up to the change, nothing after it
+      const precision = new BN(10 ** 12);
+      expect(j.accRewardPerWeight.toString()).to.equal(
+        jungleSet.accRewardPerWeight.toString()
+      );
+      expect(stakerAccountAfter.amount.toString()).to.equal(
+        stakerAccount.amount
+          .add(
+            animalBefore.weight
+              .mul(jungleSet.accRewardPerWeight)
+              .div(precision)
+              .sub(animalBefore.rewardDebt)
+          )
+          .toString()
+      );
+
+      // The animal moved to the new weight
+      expect(a.epoch.toString()).to.equal(j.epoch.toString());
+      expect(a.weight.gt(animalBefore.weight)).to.equal(true);
+      expect(j.totalWeight.toString()).to.equal(
+        jungleSet.totalWeight.sub(animalBefore.weight).add(a.weight).toString()
+      );
+      expect(j.staleAnimals.toString()).to.equal("1");
+    });
+
+    it("Settles stale animals at their old weight", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [otherAnimal] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStakedOther].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      const setJungle = () =>
+        program.rpc.setJungle(
+          maxRarity,
+          state.maxMultiplier.mul(new BN(2)),
+          state.baseWeeklyEmissions,
+          state.start,
+          tree.getRootArray(),
+          { deposit: {} },
+          lockTiers(),
+          {
+            accounts: {
+              jungle: jungleAddress,
+              owner: owner.publicKey,
+              clock: SYSVAR_CLOCK_PUBKEY,
+            },
+            signers: [owner],
+          }
+        );
+      const reweightAnimals = (animals: PublicKey[]) =>
+        program.rpc.reweightAnimals({
+          accounts: {
+            jungle: jungleAddress,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          remainingAccounts: animals.map((pubkey) => ({
+            pubkey,
+            isWritable: true,
+            isSigner: false,
+          })),
+        });
+
+      const animalBefore = await program.account.animal.fetch(animal);
+      const otherBefore = await program.account.animal.fetch(otherAnimal);
+      await setJungle();
+      const jungleSet = await program.account.jungle.fetch(jungleAddress);
+      expect(jungleSet.staleAnimals.toString()).to.equal("2");
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      // The emissions keep accruing with the previous weights
+      await reweightAnimals([animal]);
+      let j = await program.account.jungle.fetch(jungleAddress);
+      const precision = new BN(10 ** 12);
+      const emitted = state.baseWeeklyEmissions
+        .mul(j.lastUpdate.sub(jungleSet.lastUpdate))
+        .div(new BN(604800));
+      expect(j.staleAnimals.toString()).to.equal("1");
+      expect(
+        j.accRewardPerWeight.sub(jungleSet.accRewardPerWeight).toString()
+      ).to.equal(emitted.mul(precision).div(jungleSet.totalWeight).toString());
+
+      // The reweighted animal was paid at its old weight up to now
+      let a = await program.account.animal.fetch(animal);
+      expect(a.epoch.toString()).to.equal(j.epoch.toString());
+      expect(a.unpaidRewards.toString()).to.equal(
+        animalBefore.weight
+          .mul(j.accRewardPerWeight)
+          .div(precision)
+          .sub(animalBefore.rewardDebt)
+          .add(animalBefore.unpaidRewards)
+          .toString()
+      );
+
+      // Animals already reweighted are skipped
+      await reweightAnimals([animal, otherAnimal]);
+      j = await program.account.jungle.fetch(jungleAddress);
+      a = await program.account.animal.fetch(animal);
+      const other = await program.account.animal.fetch(otherAnimal);
+      expect(j.staleAnimals.toString()).to.equal("0");
+      expect(other.epoch.toString()).to.equal(j.epoch.toString());
+      expect(j.totalWeight.toString()).to.equal(
+        a.weight.add(other.weight).toString()
+      );
+      expect(other.unpaidRewards.toString()).to.equal(
+        otherBefore.weight
+          .mul(j.accRewardPerWeight)
+          .div(precision)
+          .sub(otherBefore.rewardDebt)
+          .add(otherBefore.unpaidRewards)
           .toString()
       );
     });
@@ -322,6 +575,7 @@ export const testClaimRewards = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked + 1].publicKey.toBuffer(),
         ],
         program.programId
@@ -349,6 +603,8 @@ export const testClaimRewards = (
             escrow: escrow,
             animal: animal,
             staker: holders[indexStaked + 1].publicKey,
+            authority: holders[indexStaked + 1].publicKey,
+            profile: SystemProgram.programId,
             mint: mintRewards.publicKey,
             stakerAccount: stakerAccount.address,
             rewardsAccount: rewardsAccount,
@@ -374,6 +630,7 @@ export const testClaimRewards = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStakedOther].publicKey.toBuffer(),
         ],
         program.programId
@@ -401,6 +658,8 @@ export const testClaimRewards = (
             escrow: escrow,
             animal: animal,
             staker: holders[indexStaked].publicKey,
+            authority: holders[indexStaked].publicKey,
+            profile: SystemProgram.programId,
             mint: mintRewards.publicKey,
             stakerAccount: stakerAccount.address,
             rewardsAccount: rewardsAccount,
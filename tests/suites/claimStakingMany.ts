@@ -0,0 +1,322 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testClaimStakingMany = (
+  state: {
+    owner: Keypair;
+    staker: Keypair;
+    jungleKey: PublicKey;
+    mintRewards: Token;
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Claim rewards of many animals", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 10;
+    let mintRewards: Token,
+      mints: Token[],
+      accounts: PublicKey[] = Array(n).fill(new PublicKey(0));
+    let tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair, stranger: Keypair;
+
+    const maxRarity = new BN(n);
+    const indexesStaked = [1, 4, 7];
+    const indexStranger = 2;
+
+    const findAnimal = async (index: number) =>
+      (
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("animal", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        )
+      )[0];
+
+    const stakeAnimal = async (index: number, holder: Keypair) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        faction(index % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: holder.publicKey,
+            mint: mints[index].publicKey,
+            stakerAccount: accounts[index],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[index].publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [holder],
+        }
+      );
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      stranger = Keypair.generate();
+      await airdropUsers([owner, staker, stranger], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      await Promise.all(
+        mints.map(async (mint, i) => {
+          const holder = i === indexStranger ? stranger : staker;
+          accounts[i] = (
+            await mint.getOrCreateAssociatedAccountInfo(holder.publicKey)
+          ).address;
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, accounts[i], owner, [], 1);
+        })
+      );
+      tree = nfts.tree;
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        maxRarity,
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+
+      // Mint tokens to the jungle
+      await mintRewards.mintTo(rewards, owner, [], 10 ** 14);
+
+      for (const index of indexesStaked) {
+        await stakeAnimal(index, staker);
+      }
+      await stakeAnimal(indexStranger, stranger);
+    });
+
+    it("Claims the rewards of all the animals at once", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const animals = await Promise.all(indexesStaked.map(findAnimal));
+      const animalsBefore = await Promise.all(
+        animals.map((animal) => program.account.animal.fetch(animal))
+      );
+
+      const stakerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        staker.publicKey
+      );
+
+      await program.rpc.claimStakingMany({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          staker: staker.publicKey,
+          authority: staker.publicKey,
+          profile: SystemProgram.programId,
+          mint: mintRewards.publicKey,
+          stakerAccount: stakerAccount.address,
+          rewardsAccount: rewardsAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        remainingAccounts: animals.map((animal) => ({
+          pubkey: animal,
+          isWritable: true,
+          isSigner: false,
+        })),
+        signers: [staker],
+      });
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      const animalsAfter = await Promise.all(
+        animals.map((animal) => program.account.animal.fetch(animal))
+      );
+      const stakerAccountAfter = await mintRewards.getAccountInfo(
+        stakerAccount.address
+      );
+
+      // Every animal is settled and the sum is paid in one transfer
+      const precision = new BN(10 ** 12);
+      let expected = new BN(0);
+      animalsAfter.forEach((a, i) => {
+        const accrued = a.weight.mul(j.accRewardPerWeight).div(precision);
+        expected = expected.add(accrued.sub(animalsBefore[i].rewardDebt));
+        expect(a.rewardDebt.toString()).to.equal(accrued.toString());
+        expect(a.lastClaim.gte(animalsBefore[i].lastClaim)).to.equal(true);
+      });
+      expect(stakerAccountAfter.amount.toString()).to.equal(
+        stakerAccount.amount.add(expected).toString()
+      );
+    });
+
+    it("Can't claim an animal of another staker", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const animals = await Promise.all(
+        [...indexesStaked, indexStranger].map(findAnimal)
+      );
+
+      const stakerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        staker.publicKey
+      );
+
+      await assertFail(
+        program.rpc.claimStakingMany({
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            staker: staker.publicKey,
+            authority: staker.publicKey,
+            profile: SystemProgram.programId,
+            mint: mintRewards.publicKey,
+            stakerAccount: stakerAccount.address,
+            rewardsAccount: rewardsAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          remainingAccounts: animals.map((animal) => ({
+            pubkey: animal,
+            isWritable: true,
+            isSigner: false,
+          })),
+          signers: [staker],
+        })
+      );
+    });
+  });
@@ -0,0 +1,229 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Lottery } from "../../target/types/lottery";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+export const testCommitLotteryRound = (provider: Provider) =>
+  describe("Commit a lottery round", () => {
+    setProvider(provider);
+
+    const program = workspace.Lottery as Program<Lottery>;
+
+    let owner: Keypair, player: Keypair;
+    let mintRewards: Token;
+    let lotteryKey: PublicKey, treasury: PublicKey, playerAccount: PublicKey;
+
+    const startingAmount = new BN(10 ** 10);
+    const revealWindow = new BN(100);
+    const period = new BN(1000);
+
+    beforeEach(async () => {
+      owner = Keypair.generate();
+      player = Keypair.generate();
+      await airdropUsers([owner, player], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      treasury = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(owner.publicKey)
+      ).address;
+      playerAccount = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(player.publicKey)
+      ).address;
+      lotteryKey = Keypair.generate().publicKey;
+      await mintRewards.mintTo(
+        playerAccount,
+        owner,
+        [],
+        startingAmount.toNumber()
+      );
+
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+
+      const bumps = {
+        lottery: lotteryBump,
+        escrow: escrowBump,
+        round: roundBump,
+      };
+
+      const start = Math.round(Date.now() / 1000);
+
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
+        accounts: {
+          lotteryKey: lotteryKey,
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          escrow: escrow,
+          mint: mintRewards.publicKey,
+          treasury: treasury,
+          owner: owner.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [owner],
+      });
+    });
+
+    it("Commits the current round", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+
+      const secret = generateSecret();
+      await program.rpc.commitLotteryRound(secret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      const r = await program.account.lotteryRound.fetch(round);
+      expect(r.commitment.toString()).to.equal(secret.commitment.toString());
+
+      // The commitment can't be replaced
+      await assertFail(
+        program.rpc.commitLotteryRound(generateSecret().commitment, {
+          accounts: {
+            lottery: lotteryAddress,
+            lotteryRound: round,
+            owner: owner.publicKey,
+          },
+          signers: [owner],
+        })
+      );
+    });
+
+    it("Fails when not called by the owner", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+
+      await assertFail(
+        program.rpc.commitLotteryRound(generateSecret().commitment, {
+          accounts: {
+            lottery: lotteryAddress,
+            lotteryRound: round,
+            owner: player.publicKey,
+          },
+          signers: [player],
+        })
+      );
+    });
+
+    it("Players can't enter the round before it is committed", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      const [participation, participationBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("participation"),
+            lotteryKey.toBuffer(),
+            new BN(0).toBuffer("le", 8),
+            player.publicKey.toBytes(),
+          ],
+          program.programId
+        );
+
+      const spendings = Array(8)
+        .fill(10 ** 9)
+        .map((e) => new BN(e));
+
+      const participate = () =>
+        program.rpc.participate(participationBump, spendings, {
+          accounts: {
+            lottery: lotteryAddress,
+            lotteryRound: round,
+            participation: participation,
+            player: player.publicKey,
+            playerAccount: playerAccount,
+            treasury: treasury,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [player],
+        });
+
+      // The secret can't be picked once the spendings are known
+      await assertFail(participate());
+
+      await program.rpc.commitLotteryRound(generateSecret().commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+      await participate();
+
+      const r = await program.account.lotteryRound.fetch(round);
+      expect(r.spendings[0].toString()).to.equal(spendings[0].toString());
+    });
+  });
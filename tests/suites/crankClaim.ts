@@ -0,0 +1,291 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
+import {
+  Token,
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testCrankClaim = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Crank the claim of rewards", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 4;
+    const index = 1;
+    let mintRewards: Token, mints: Token[], tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+    let animal: PublicKey;
+
+    const crankClaim = async (caller: Keypair, callerAccount: PublicKey) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const stakerAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        mintRewards.publicKey,
+        staker.publicKey
+      );
+
+      await program.rpc.crankClaim({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: staker.publicKey,
+          stakerAccount: stakerAccount,
+          mint: mintRewards.publicKey,
+          rewardsAccount: rewardsAccount,
+          caller: caller.publicKey,
+          callerAccount: callerAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [caller],
+      });
+      return stakerAccount;
+    };
+
+    const stakeAnimal = async (index: number) => {
+      const mint = mints[index];
+      const stakerAccount = (
+        await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        faction(index % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mint.publicKey,
+            stakerAccount: stakerAccount,
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mint.publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return animal;
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      tree = nfts.tree;
+      await Promise.all(
+        mints.map(async (mint) => {
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          const stakerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, stakerAccount, owner, [], 1);
+        })
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+      await mintRewards.mintTo(rewards, owner, [], 10 ** 14);
+
+      animal = await stakeAnimal(index);
+    });
+
+    it("Pays the rewards to the staker's new associated account", async () => {
+      const keeper = Keypair.generate();
+      await airdropUsers([keeper], provider);
+      const before = await program.account.animal.fetch(animal);
+
+      const stakerAccount = await crankClaim(keeper, SystemProgram.programId);
+
+      const rewards = await mintRewards.getAccountInfo(stakerAccount);
+      const after = await program.account.animal.fetch(animal);
+      expect(rewards.owner.toString()).to.equal(staker.publicKey.toString());
+      expect(rewards.amount.toNumber()).to.be.above(0);
+      expect(after.lastClaim.gt(before.lastClaim)).to.equal(true);
+
+      // The account is reused by the next crank
+      await crankClaim(keeper, SystemProgram.programId);
+    });
+
+    it("Doesn't tip for an animal claimed within the week", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      await program.rpc.setCrankTip(new BN(1000), {
+        accounts: {
+          jungle: jungleAddress,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      const keeper = Keypair.generate();
+      await airdropUsers([keeper], provider);
+      const keeperAccount = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(keeper.publicKey)
+      ).address;
+
+      await crankClaim(keeper, keeperAccount);
+
+      const tip = await mintRewards.getAccountInfo(keeperAccount);
+      expect(tip.amount.toNumber()).to.equal(0);
+    });
+
+    it("Only the owner sets the tip", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+
+      await assertFail(
+        program.rpc.setCrankTip(new BN(1000), {
+          accounts: {
+            jungle: jungleAddress,
+            owner: staker.publicKey,
+          },
+          signers: [staker],
+        })
+      );
+    });
+  });
@@ -0,0 +1,357 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import { Lottery } from "../../target/types/lottery";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  faction,
+  findAttributesAddress,
+  generateSecret,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testEnterLottery = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Enter the lottery with staking rewards", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+    const lotteryProgram = workspace.Lottery as Program<Lottery>;
+
+    const n = 4;
+    const index = 1;
+    let mintRewards: Token, mints: Token[], tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+    let lotteryKey: PublicKey, treasury: PublicKey;
+    let animal: PublicKey;
+
+    const enterLottery = async (spendings: BN[]) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [lotteryAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        lotteryProgram.programId
+      );
+      const [round] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        lotteryProgram.programId
+      );
+      const [participation, participationBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("participation"),
+            lotteryKey.toBuffer(),
+            new BN(1).toBuffer("le", 8),
+            staker.publicKey.toBytes(),
+          ],
+          lotteryProgram.programId
+        );
+
+      await program.rpc.enterLottery(participationBump, spendings, {
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: staker.publicKey,
+          rewardsAccount: rewardsAccount,
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          participation: participation,
+          treasury: treasury,
+          lotteryProgram: lotteryProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [staker],
+      });
+      return participation;
+    };
+
+    const stakeAnimal = async (index: number) => {
+      const mint = mints[index];
+      const stakerAccount = (
+        await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        faction(index % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mint.publicKey,
+            stakerAccount: stakerAccount,
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mint.publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return animal;
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      tree = nfts.tree;
+      await Promise.all(
+        mints.map(async (mint) => {
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          const stakerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, stakerAccount, owner, [], 1);
+        })
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+      await mintRewards.mintTo(rewards, owner, [], 10 ** 14);
+
+      animal = await stakeAnimal(index);
+
+      // A lottery on the same token, with its first round opened
+      lotteryKey = Keypair.generate().publicKey;
+      treasury = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(owner.publicKey)
+      ).address;
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        lotteryProgram.programId
+      );
+      const [lotteryEscrow, lotteryEscrowBump] =
+        await PublicKey.findProgramAddress(
+          [Buffer.from("escrow"), lotteryKey.toBuffer()],
+          lotteryProgram.programId
+        );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        lotteryProgram.programId
+      );
+      const [nextRound, nextRoundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        lotteryProgram.programId
+      );
+
+      await lotteryProgram.rpc.initializeLottery(
+        { lottery: lotteryBump, escrow: lotteryEscrowBump, round: roundBump },
+        new BN(5),
+        new BN(Math.round(Date.now() / 1000) - 6),
+        new BN(100),
+        {
+          accounts: {
+            lotteryKey: lotteryKey,
+            lottery: lotteryAddress,
+            lotteryRound: round,
+            escrow: lotteryEscrow,
+            mint: mintRewards.publicKey,
+            treasury: treasury,
+            owner: owner.publicKey,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+      await lotteryProgram.rpc.newLotteryRound(nextRoundBump, null, {
+        accounts: {
+          lottery: lotteryAddress,
+          escrow: lotteryEscrow,
+          lotteryRound: nextRound,
+          oldLotteryRound: round,
+          randomness: SystemProgram.programId,
+          payer: staker.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [staker],
+      });
+      await lotteryProgram.rpc.commitLotteryRound(generateSecret().commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: nextRound,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+    });
+
+    it("Spends pending rewards in the lottery", async () => {
+      const spendings = Array(8)
+        .fill(10)
+        .map((e) => new BN(e));
+
+      const participation = await enterLottery(spendings);
+
+      const p = await lotteryProgram.account.lotteryParticipation.fetch(
+        participation
+      );
+      const t = await mintRewards.getAccountInfo(treasury);
+      expect(p.player.toString()).to.equal(staker.publicKey.toString());
+      expect(p.spendings.map((e) => e.toString()).toString()).to.equal(
+        spendings.map((e) => e.toString()).toString()
+      );
+      expect(t.amount.toNumber()).to.equal(80);
+
+      // The participation is updated by the next entry
+      await enterLottery(spendings);
+      const updated = await lotteryProgram.account.lotteryParticipation.fetch(
+        participation
+      );
+      expect(updated.spendings[0].toNumber()).to.equal(20);
+    });
+
+    it("Doesn't spend more than the pending rewards", async () => {
+      const spendings = Array(8)
+        .fill(10 ** 13)
+        .map((e) => new BN(e));
+
+      await assertFail(enterLottery(spendings));
+    });
+  });
@@ -0,0 +1,460 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
+  Transaction,
+} from "@solana/web3.js";
+import { Lottery } from "../../target/types/lottery";
+import { MockOracle } from "../../target/types/mock_oracle";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+export const testFulfilLotteryDraw = (provider: Provider) =>
+  describe("Fulfil a lottery draw", () => {
+    setProvider(provider);
+
+    const program = workspace.Lottery as Program<Lottery>;
+    const oracle = workspace.MockOracle as Program<MockOracle>;
+
+    let owner: Keypair, player: Keypair, request: Keypair;
+    let mintRewards: Token;
+    let lotteryKey: PublicKey, treasury: PublicKey, playerAccount: PublicKey;
+
+    const startingAmount = new BN(10 ** 10);
+    const revealWindow = new BN(100);
+    const period = new BN(5);
+    const potMoney = new BN(10 ** 9);
+
+    const findRound = async (index: number) =>
+      await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(index).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+
+    const requestRandomness = async () => {
+      const result = Keypair.generate();
+      await oracle.rpc.requestRandomness({
+        accounts: {
+          result: result.publicKey,
+          authority: owner.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [owner, result],
+      });
+      return result;
+    };
+
+    const fulfilRandomness = async (result: Keypair) =>
+      await oracle.rpc.fulfilRandomness(
+        Array.from(Keypair.generate().publicKey.toBytes()),
+        {
+          accounts: {
+            result: result.publicKey,
+            authority: owner.publicKey,
+          },
+          signers: [owner],
+        }
+      );
+
+    beforeEach(async () => {
+      owner = Keypair.generate();
+      player = Keypair.generate();
+      await airdropUsers([owner, player], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      treasury = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(owner.publicKey)
+      ).address;
+      playerAccount = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(player.publicKey)
+      ).address;
+      lotteryKey = Keypair.generate().publicKey;
+      await mintRewards.mintTo(
+        playerAccount,
+        owner,
+        [],
+        startingAmount.toNumber()
+      );
+
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await findRound(0);
+      const [nextRound, nextRoundBump] = await findRound(1);
+
+      const bumps = {
+        lottery: lotteryBump,
+        escrow: escrowBump,
+        round: roundBump,
+      };
+
+      const start = Math.round(Date.now() / 1000) - 6;
+
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
+        accounts: {
+          lotteryKey: lotteryKey,
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          escrow: escrow,
+          mint: mintRewards.publicKey,
+          treasury: treasury,
+          owner: owner.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [owner],
+      });
+
+      // Draw the rounds with the mock oracle
+      await program.rpc.setLottery(
+        new BN(start),
+        mintRewards.publicKey,
+        treasury,
+        period,
+        revealWindow,
+        oracle.programId,
+        {
+          accounts: {
+            lottery: lotteryAddress,
+            owner: owner.publicKey,
+          },
+          signers: [owner],
+        }
+      );
+
+      const secret = generateSecret();
+      await program.rpc.commitLotteryRound(secret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      // Send money to the pot of the next round
+      await provider.send(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: owner.publicKey,
+            toPubkey: escrow,
+            lamports: potMoney.toNumber(),
+          })
+        ),
+        [owner]
+      );
+
+      request = await requestRandomness();
+
+      await program.rpc.newLotteryRound(nextRoundBump, secret.secret, {
+        accounts: {
+          lottery: lotteryAddress,
+          escrow: escrow,
+          lotteryRound: nextRound,
+          oldLotteryRound: round,
+          randomness: request.publicKey,
+          payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [player],
+      });
+    });
+
+    it("Draws the round with the oracle's randomness", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await findRound(0);
+      const [openRound, openRoundBump] = await findRound(1);
+
+      let r = await program.account.lotteryRound.fetch(round);
+      let l = await program.account.lottery.fetch(lotteryAddress);
+      expect(r.status).to.deep.equal({ drawing: {} });
+      expect(r.winner).to.equal(0);
+      expect(l.pendingRequest.toString()).to.equal(
+        request.publicKey.toString()
+      );
+
+      // The oracle didn't answer yet
+      await assertFail(
+        program.rpc.fulfilLotteryDraw({
+          accounts: {
+            lottery: lotteryAddress,
+            lotteryRound: round,
+            openLotteryRound: openRound,
+            randomness: request.publicKey,
+          },
+        })
+      );
+
+      await fulfilRandomness(request);
+
+      await program.rpc.fulfilLotteryDraw({
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          openLotteryRound: openRound,
+          randomness: request.publicKey,
+        },
+      });
+
+      r = await program.account.lotteryRound.fetch(round);
+      l = await program.account.lottery.fetch(lotteryAddress);
+      expect(r.status).to.deep.equal({ drawn: {} });
+      expect(r.winner).to.not.equal(0);
+      expect(l.pendingRequest.toString()).to.equal(
+        new PublicKey(0).toString()
+      );
+    });
+
+    it("Can't start a new round while a draw is pending", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await findRound(1);
+      const [nextRound, nextRoundBump] = await findRound(2);
+
+      await new Promise(async (resolve) => setTimeout(resolve, 6000));
+
+      await assertFail(
+        program.rpc.newLotteryRound(nextRoundBump, null, {
+          accounts: {
+            lottery: lotteryAddress,
+            escrow: escrow,
+            lotteryRound: nextRound,
+            oldLotteryRound: round,
+            randomness: (await requestRandomness()).publicKey,
+            payer: player.publicKey,
+            slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [player],
+        })
+      );
+    });
+
+    it("Rolls the pot of a round nobody won into the open round", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [previousRound, previousRoundBump] = await findRound(0);
+      const [round, roundBump] = await findRound(1);
+      const [nextRound, nextRoundBump] = await findRound(2);
+
+      // Settle the draw of the first round
+      await fulfilRandomness(request);
+      await program.rpc.fulfilLotteryDraw({
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: previousRound,
+          openLotteryRound: round,
+          randomness: request.publicKey,
+        },
+      });
+
+      // Wait for the end of a round whose secret was never committed
+      await new Promise(async (resolve) => setTimeout(resolve, 6000));
+
+      const nextRequest = await requestRandomness();
+      await program.rpc.newLotteryRound(nextRoundBump, null, {
+        accounts: {
+          lottery: lotteryAddress,
+          escrow: escrow,
+          lotteryRound: nextRound,
+          oldLotteryRound: round,
+          randomness: nextRequest.publicKey,
+          payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [player],
+      });
+
+      // The oracle still draws the round
+      let r = await program.account.lotteryRound.fetch(round);
+      let nr = await program.account.lotteryRound.fetch(nextRound);
+      expect(r.status).to.deep.equal({ drawing: {} });
+      expect(r.pot.toString()).to.equal(potMoney.toString());
+      expect(nr.pot.toString()).to.equal(new BN(0).toString());
+
+      await fulfilRandomness(nextRequest);
+      await program.rpc.fulfilLotteryDraw({
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          openLotteryRound: nextRound,
+          randomness: nextRequest.publicKey,
+        },
+      });
+
+      r = await program.account.lotteryRound.fetch(round);
+      nr = await program.account.lotteryRound.fetch(nextRound);
+      expect(r.status).to.deep.equal({ drawn: {} });
+      expect(r.pot.toString()).to.equal(new BN(0).toString());
+      expect(nr.pot.toString()).to.equal(potMoney.toString());
+    });
+
+    it("Refuses to pay participations before the draw", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [previousRound, previousRoundBump] = await findRound(0);
+      const [round, roundBump] = await findRound(1);
+      const [nextRound, nextRoundBump] = await findRound(2);
+      const [participation, participationBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("participation"),
+            lotteryKey.toBuffer(),
+            new BN(1).toBuffer("le", 8),
+            player.publicKey.toBytes(),
+          ],
+          program.programId
+        );
+
+      // Settle the draw of the first round
+      await fulfilRandomness(request);
+      await program.rpc.fulfilLotteryDraw({
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: previousRound,
+          openLotteryRound: round,
+          randomness: request.publicKey,
+        },
+      });
+
+      const secret = generateSecret();
+      await program.rpc.commitLotteryRound(secret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      const spendings = Array(8)
+        .fill(10 ** 9)
+        .map((e) => new BN(e));
+
+      await program.rpc.participate(participationBump, spendings, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          participation: participation,
+          player: player.publicKey,
+          playerAccount: playerAccount,
+          treasury: treasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [player],
+      });
+
+      // Wait for the end of the round
+      await new Promise(async (resolve) => setTimeout(resolve, 6000));
+
+      const nextRequest = await requestRandomness();
+      await program.rpc.newLotteryRound(nextRoundBump, secret.secret, {
+        accounts: {
+          lottery: lotteryAddress,
+          escrow: escrow,
+          lotteryRound: nextRound,
+          oldLotteryRound: round,
+          randomness: nextRequest.publicKey,
+          payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [player],
+      });
+
+      const claimAccounts = {
+        lottery: lotteryAddress,
+        escrow: escrow,
+        lotteryRound: round,
+        participation: participation,
+        player: player.publicKey,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      };
+
+      await assertFail(
+        program.rpc.claimParticipation({
+          accounts: claimAccounts,
+          signers: [player],
+        })
+      );
+
+      await fulfilRandomness(nextRequest);
+      await program.rpc.fulfilLotteryDraw({
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          openLotteryRound: nextRound,
+          randomness: nextRequest.publicKey,
+        },
+      });
+
+      await program.rpc.claimParticipation({
+        accounts: claimAccounts,
+        signers: [player],
+      });
+    });
+  });
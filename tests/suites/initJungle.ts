@@ -95,6 +95,7 @@ export const testInitializeJungle = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        new BN(1),
         {
           accounts: {
             jungleKey: state.jungleKey,
@@ -127,6 +128,7 @@ export const testInitializeJungle = (
       expect(s.root.toString()).to.equal(
         tree.getRoot().toJSON().data.toString()
       );
+      expect(s.withdrawalDelay.toNumber()).to.equal(1);
     });
 
     it("Only accepts positive multipliers", async () => {
@@ -164,6 +166,61 @@ export const testInitializeJungle = (
           state.baseWeeklyEmissions,
           state.start,
           tree.getRootArray(),
+          new BN(1),
+          {
+            accounts: {
+              jungleKey: jungleKey,
+              jungle: jungleAddress,
+              escrow: escrow,
+              mint: mintRewards.publicKey,
+              rewardsAccount: rewards,
+              owner: state.owner.publicKey,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: SYSVAR_RENT_PUBKEY,
+              systemProgram: SystemProgram.programId,
+            },
+            signers: [state.owner],
+          }
+        )
+      );
+    });
+
+    it("Requires a withdrawal delay", async () => {
+      const jungleKey = Keypair.generate().publicKey;
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      const bumps = {
+        jungle: jungleBump,
+        escrow: escrowBump,
+        rewards: rewardsBump,
+      };
+
+      const maximumRarity = new BN(mints.length - 1);
+
+      await assertFail(
+        program.rpc.initializeJungle(
+          bumps,
+          maximumRarity,
+          state.maxMultiplier,
+          state.baseWeeklyEmissions,
+          state.start,
+          tree.getRootArray(),
+          new BN(0),
           {
             accounts: {
               jungleKey: jungleKey,
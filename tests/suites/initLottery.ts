@@ -32,6 +32,7 @@ export const testInitializeLottery = (
 
     const startingAmount = new BN(10 ** 10);
     const period = new BN(5)
+    const revealWindow = new BN(100)
 
     before(async () => {
       owner = Keypair.generate();
@@ -86,6 +87,7 @@ export const testInitializeLottery = (
         bumps,
         period,
         new BN(start),
+        revealWindow,
         {
           accounts: {
             lotteryKey: lotteryKey,
@@ -110,6 +112,7 @@ export const testInitializeLottery = (
       expect(s.escrow.toString()).to.equal(escrow.toString());
       expect(s.mint.toString()).to.equal(mintRewards.publicKey.toString());
       expect(s.period.toString()).to.equal(period.toString());
+      expect(s.revealWindow.toString()).to.equal(revealWindow.toString());
       expect(s.treasury.toString()).to.equal(treasury.toString());
     });
   });
@@ -0,0 +1,240 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import fs from "fs";
+import { Jungle } from "../../target/types/jungle";
+import { airdropUsers, assertFail, faction } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+// A jungle and an animal as serialized by the program before the migration,
+// loaded in the local validator by Anchor.toml
+const legacy = JSON.parse(
+  fs.readFileSync("./tests/fixtures/legacy.json").toString()
+);
+const keypair = (name: string) =>
+  Keypair.fromSecretKey(
+    Uint8Array.from(
+      JSON.parse(fs.readFileSync(`./tests/fixtures/${name}.json`).toString())
+    )
+  );
+
+export const testMigrateAnimal = (provider: Provider) =>
+  describe("Migrate legacy accounts", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const owner = keypair("legacyOwner");
+    const staker = keypair("legacyStaker");
+    const jungleKey = new PublicKey(legacy.jungleKey);
+    const mint = new PublicKey(legacy.mint);
+    const mintRewards = new PublicKey(legacy.mintRewards);
+
+    let jungleAddress: PublicKey,
+      escrow: PublicKey,
+      rewardsAccount: PublicKey,
+      legacyAnimal: PublicKey,
+      legacyDeposit: PublicKey,
+      stakerRewardsAccount: PublicKey,
+      stakerAccount: PublicKey;
+
+    const migrateAnimal = async () => {
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("animal"), jungleKey.toBuffer(), mint.toBuffer()],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("deposit"), jungleKey.toBuffer(), mint.toBuffer()],
+        program.programId
+      );
+      await program.rpc.migrateAnimal(
+        { animal: animalBump, deposit: depositBump },
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            legacyAnimal: legacyAnimal,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mint,
+            legacyDepositAccount: legacyDeposit,
+            depositAccount: deposit,
+            rewardsAccount: rewardsAccount,
+            stakerRewardsAccount: stakerRewardsAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return { animal, deposit };
+    };
+
+    const migrateJungle = (signer: Keypair) =>
+      program.rpc.migrateJungle({
+        accounts: {
+          jungle: jungleAddress,
+          owner: signer.publicKey,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [signer],
+      });
+
+    before(async () => {
+      await airdropUsers([owner, staker], provider);
+
+      [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.toBuffer(),
+        ],
+        program.programId
+      );
+      [legacyAnimal] = await PublicKey.findProgramAddress(
+        [Buffer.from("animal"), mint.toBuffer()],
+        program.programId
+      );
+      [legacyDeposit] = await PublicKey.findProgramAddress(
+        [Buffer.from("deposit"), mint.toBuffer()],
+        program.programId
+      );
+
+      stakerRewardsAccount = await new Token(
+        provider.connection,
+        mintRewards,
+        TOKEN_PROGRAM_ID,
+        staker
+      ).createAccount(staker.publicKey);
+      stakerAccount = await new Token(
+        provider.connection,
+        mint,
+        TOKEN_PROGRAM_ID,
+        staker
+      ).createAccount(staker.publicKey);
+    });
+
+    it("Can't migrate an animal before its jungle", async () => {
+      await assertFail(migrateAnimal());
+    });
+
+    it("Migrates a jungle from the legacy layout", async () => {
+      await assertFail(migrateJungle(staker));
+      await migrateJungle(owner);
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      expect(j.key.toString()).to.equal(jungleKey.toString());
+      expect(j.owner.toString()).to.equal(owner.publicKey.toString());
+      expect(j.mint.toString()).to.equal(mintRewards.toString());
+      expect(j.animalsStaked.toString()).to.equal("1");
+      expect(j.maximumRarity.toString()).to.equal(
+        legacy.maximumRarity.toString()
+      );
+      expect(j.baseWeeklyEmissions.toString()).to.equal(
+        legacy.baseWeeklyEmissions.toString()
+      );
+      expect(j.totalWeight.toString()).to.equal("0");
+      expect(j.lockTiers[0].multiplier.toString()).to.equal("10000");
+      expect(j.withdrawalDelay.toNumber()).to.equal(172800);
+
+      // The jungle already has the current layout
+      await assertFail(migrateJungle(owner));
+    });
+
+    it("Migrates an animal from the legacy layout", async () => {
+      const { animal, deposit } = await migrateAnimal();
+
+      const a = await program.account.animal.fetch(animal);
+      expect(a.mint.toString()).to.equal(mint.toString());
+      expect(a.staker.toString()).to.equal(staker.publicKey.toString());
+      expect(a.jungle.toString()).to.equal(jungleAddress.toString());
+      expect(a.rarity.toString()).to.equal(legacy.rarity.toString());
+      expect(a.faction).to.deep.equal(faction(legacy.faction));
+      expect(a.weight.toString()).to.equal("15000");
+
+      // The animal joins the weights, but was already counted as staked
+      const j = await program.account.jungle.fetch(jungleAddress);
+      expect(j.animalsStaked.toString()).to.equal("1");
+      expect(j.totalWeight.toString()).to.equal(a.weight.toString());
+      expect(j.factionStats[legacy.faction].animals.toString()).to.equal("1");
+      expect(j.factionStats[legacy.faction].weight.toString()).to.equal(
+        a.weight.toString()
+      );
+
+      // The rewards earned since the last legacy claim are paid at the legacy rate
+      const expected = new BN(legacy.baseWeeklyEmissions)
+        .mul(a.weight)
+        .div(new BN(10000))
+        .mul(a.lastClaim.sub(new BN(legacy.lastClaim)))
+        .div(new BN(604800));
+      const paid = await new Token(
+        provider.connection,
+        mintRewards,
+        TOKEN_PROGRAM_ID,
+        staker
+      ).getAccountInfo(stakerRewardsAccount);
+      expect(paid.amount.toString()).to.equal(expected.toString());
+      expect(a.unpaidRewards.toString()).to.equal("0");
+
+      const token = new Token(
+        provider.connection,
+        mint,
+        TOKEN_PROGRAM_ID,
+        staker
+      );
+      expect((await token.getAccountInfo(deposit)).amount.toString()).to.equal(
+        "1"
+      );
+      expect(await provider.connection.getAccountInfo(legacyAnimal)).to.be
+        .null;
+      expect(await provider.connection.getAccountInfo(legacyDeposit)).to.be
+        .null;
+
+      // The migrated animal can leave the jungle
+      await program.rpc.unstakeAnimal({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: staker.publicKey,
+          mint: mint,
+          stakerAccount: stakerAccount,
+          depositAccount: deposit,
+          edition: SystemProgram.programId,
+          tokenMetadataProgram: SystemProgram.programId,
+          rewardsAccount: SystemProgram.programId,
+          stakerRewardsAccount: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        signers: [staker],
+      });
+      expect(
+        (await token.getAccountInfo(stakerAccount)).amount.toString()
+      ).to.equal("1");
+    });
+  });
@@ -0,0 +1,149 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import fs from "fs";
+import { Lottery } from "../../target/types/lottery";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
+
+// A lottery and its rounds as serialized by the program before the migration,
+// loaded in the local validator by Anchor.toml
+const legacy = JSON.parse(
+  fs.readFileSync("./tests/fixtures/legacy.json").toString()
+);
+const owner = Keypair.fromSecretKey(
+  Uint8Array.from(
+    JSON.parse(fs.readFileSync("./tests/fixtures/legacyOwner.json").toString())
+  )
+);
+
+export const testMigrateLottery = (provider: Provider) =>
+  describe("Migrate legacy lottery accounts", () => {
+    setProvider(provider);
+
+    const program = workspace.Lottery as Program<Lottery>;
+
+    const lotteryKey = new PublicKey(legacy.lotteryKey);
+    const stranger = Keypair.generate();
+
+    let lotteryAddress: PublicKey,
+      drawnRound: PublicKey,
+      openRound: PublicKey;
+
+    const migrateLottery = (signer: Keypair) =>
+      program.rpc.migrateLottery({
+        accounts: {
+          lottery: lotteryAddress,
+          owner: signer.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [signer],
+      });
+
+    const migrateLotteryRound = (round: PublicKey, signer: Keypair) =>
+      program.rpc.migrateLotteryRound({
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: signer.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [signer],
+      });
+
+    before(async () => {
+      await airdropUsers([owner, stranger], provider);
+
+      [lotteryAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      [drawnRound] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      [openRound] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+    });
+
+    it("Can't migrate a round before its lottery", async () => {
+      await assertFail(migrateLotteryRound(openRound, owner));
+    });
+
+    it("Migrates a lottery from the legacy layout", async () => {
+      await assertFail(migrateLottery(stranger));
+      await migrateLottery(owner);
+
+      const l = await program.account.lottery.fetch(lotteryAddress);
+      expect(l.key.toString()).to.equal(lotteryKey.toString());
+      expect(l.owner.toString()).to.equal(owner.publicKey.toString());
+      expect(l.mint.toString()).to.equal(legacy.lotteryMint);
+      expect(l.treasury.toString()).to.equal(legacy.lotteryTreasury);
+      expect(l.period.toNumber()).to.equal(legacy.lotteryPeriod);
+      expect(l.lastRound.toNumber()).to.equal(1);
+      expect(l.lastTimestamp.toNumber()).to.equal(legacy.lotteryLastTimestamp);
+      expect(l.unclaimedPot.toNumber()).to.equal(legacy.lotteryUnclaimedPot);
+      expect(l.revealWindow.toNumber()).to.equal(86400);
+      expect(l.oracle.toString()).to.equal(PublicKey.default.toString());
+      expect(l.pendingOwner.toString()).to.equal(PublicKey.default.toString());
+
+      // The lottery already has the current layout
+      await assertFail(migrateLottery(owner));
+    });
+
+    it("Migrates the rounds from the legacy layout", async () => {
+      await assertFail(migrateLotteryRound(drawnRound, stranger));
+      await migrateLotteryRound(drawnRound, owner);
+      await migrateLotteryRound(openRound, owner);
+
+      // The round with a winner is drawn
+      const drawn = await program.account.lotteryRound.fetch(drawnRound);
+      expect(drawn.index.toNumber()).to.equal(0);
+      expect(drawn.winner).to.equal(legacy.drawnRoundWinner);
+      expect(drawn.pot.toNumber()).to.equal(legacy.drawnRoundPot);
+      expect(drawn.status).to.deep.equal({ drawn: {} });
+
+      // The current round is still open, and waits for its commitment
+      const open = await program.account.lotteryRound.fetch(openRound);
+      expect(open.index.toNumber()).to.equal(1);
+      expect(open.pot.toNumber()).to.equal(legacy.openRoundPot);
+      expect(open.spendings[1].toNumber()).to.equal(100);
+      expect(open.status).to.deep.equal({ open: {} });
+      expect(open.commitment).to.deep.equal(Array(32).fill(0));
+
+      const secret = generateSecret();
+      await program.rpc.commitLotteryRound(secret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: openRound,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      // The rounds already have the current layout
+      await assertFail(migrateLotteryRound(openRound, owner));
+    });
+  });
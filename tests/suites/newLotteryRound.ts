@@ -13,9 +13,10 @@ import {
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
   SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
 } from "@solana/web3.js";
 import { Lottery } from "../../target/types/lottery";
-import { airdropUsers, assertFail } from "../helpers";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 export const testNewLotteryRound = (provider: Provider) =>
@@ -27,8 +28,10 @@ export const testNewLotteryRound = (provider: Provider) =>
     let owner: Keypair, player: Keypair;
     let mintRewards: Token;
     let lotteryKey: PublicKey, treasury: PublicKey, playerAccount: PublicKey;
+    let secret: { secret: number[]; commitment: number[] };
 
     const startingAmount = new BN(10 ** 10);
+    const revealWindow = new BN(100);
     const period = new BN(5);
 
     beforeEach(async () => {
@@ -83,7 +86,7 @@ export const testNewLotteryRound = (provider: Provider) =>
 
       const start = Math.round(Date.now() / 1000) - 6;
 
-      await program.rpc.initializeLottery(bumps, period, new BN(start), {
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
         accounts: {
           lotteryKey: lotteryKey,
           lottery: lotteryAddress,
@@ -97,6 +100,16 @@ export const testNewLotteryRound = (provider: Provider) =>
         },
         signers: [owner],
       });
+
+      secret = generateSecret();
+      await program.rpc.commitLotteryRound(secret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
     });
 
     it("Start a new round", async () => {
@@ -140,13 +153,15 @@ export const testNewLotteryRound = (provider: Provider) =>
         [owner]
       );
 
-      await program.rpc.newLotteryRound(roundBump, {
+      await program.rpc.newLotteryRound(roundBump, secret.secret, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: round,
           oldLotteryRound: previousRound,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
@@ -167,7 +182,18 @@ export const testNewLotteryRound = (provider: Provider) =>
       expect(pr.pot.toString()).to.equal(new BN(0).toString());
       expect(pr.winner).to.not.equal(0);
 
-      // Not claiming anything and skipping more rounds should only give empty pots
+      // Commit the secret of the round that just started
+      const nextSecret = generateSecret();
+      await program.rpc.commitLotteryRound(nextSecret.commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      // Nobody entered the round, so its pot goes to the next one
       await new Promise(async (resolve) => setTimeout(resolve, 6000))
 
       const [nextRound, nextRoundBump] =
@@ -180,13 +206,15 @@ export const testNewLotteryRound = (provider: Provider) =>
           program.programId
         );
 
-      await program.rpc.newLotteryRound(nextRoundBump, {
+      await program.rpc.newLotteryRound(nextRoundBump, nextSecret.secret, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: nextRound,
           oldLotteryRound: round,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
@@ -202,7 +230,7 @@ export const testNewLotteryRound = (provider: Provider) =>
       expect(r.spendings.map((e) => e.toString()).toString()).to.equal(
         new Array(8).fill("0").toString()
       );
-      expect(r.pot.toString()).to.equal(potMoney.toString());
+      expect(r.pot.toString()).to.equal(new BN(0).toString());
       expect(r.winner).to.not.equal(0);
 
       expect(nr.index.toNumber()).to.equal(2);
@@ -210,7 +238,7 @@ export const testNewLotteryRound = (provider: Provider) =>
       expect(nr.spendings.map((e) => e.toString()).toString()).to.equal(
         new Array(8).fill("0").toString()
       );
-      expect(nr.pot.toString()).to.equal(new BN(0).toString());
+      expect(nr.pot.toString()).to.equal(potMoney.toString());
       expect(nr.winner).to.equal(0);
     });
 
@@ -263,13 +291,15 @@ export const testNewLotteryRound = (provider: Provider) =>
         [owner]
       );
 
-      await program.rpc.newLotteryRound(roundBump, {
+      await program.rpc.newLotteryRound(roundBump, secret.secret, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: round,
           oldLotteryRound: previousRound,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
@@ -278,13 +308,15 @@ export const testNewLotteryRound = (provider: Provider) =>
       });
 
       await assertFail(
-        program.rpc.newLotteryRound(nextRoundBump, {
+        program.rpc.newLotteryRound(nextRoundBump, null, {
           accounts: {
             lottery: lotteryAddress,
             escrow: escrow,
             lotteryRound: nextRound,
             oldLotteryRound: round,
+            randomness: SystemProgram.programId,
             payer: player.publicKey,
+            slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             clock: SYSVAR_CLOCK_PUBKEY,
             rent: SYSVAR_RENT_PUBKEY,
             systemProgram: SystemProgram.programId,
@@ -293,4 +325,135 @@ export const testNewLotteryRound = (provider: Provider) =>
         })
       );
     });
+
+    it("Rejects a secret that doesn't match the commitment", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      const [previousRound, previousRoundBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("round"),
+            lotteryKey.toBuffer(),
+            new BN(0).toBuffer("le", 8),
+          ],
+          program.programId
+        );
+
+      const accounts = {
+        lottery: lotteryAddress,
+        escrow: escrow,
+        lotteryRound: round,
+        oldLotteryRound: previousRound,
+        randomness: SystemProgram.programId,
+        payer: player.publicKey,
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      };
+
+      // A wrong secret can't draw the round
+      await assertFail(
+        program.rpc.newLotteryRound(roundBump, generateSecret().secret, {
+          accounts,
+          signers: [player],
+        })
+      );
+
+      // The round can't be closed without a reveal while the window is open
+      await assertFail(
+        program.rpc.newLotteryRound(roundBump, null, {
+          accounts,
+          signers: [player],
+        })
+      );
+    });
+
+    it("Closes a round without winner when nothing was committed", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      const [nextRound, nextRoundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(2).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      const [previousRound, previousRoundBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("round"),
+            lotteryKey.toBuffer(),
+            new BN(0).toBuffer("le", 8),
+          ],
+          program.programId
+        );
+
+      await program.rpc.newLotteryRound(roundBump, secret.secret, {
+        accounts: {
+          lottery: lotteryAddress,
+          escrow: escrow,
+          lotteryRound: round,
+          oldLotteryRound: previousRound,
+          randomness: SystemProgram.programId,
+          payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [player],
+      });
+
+      // Wait for the end of the round, that was never committed
+      await new Promise(async (resolve) => setTimeout(resolve, 6000));
+
+      await program.rpc.newLotteryRound(nextRoundBump, null, {
+        accounts: {
+          lottery: lotteryAddress,
+          escrow: escrow,
+          lotteryRound: nextRound,
+          oldLotteryRound: round,
+          randomness: SystemProgram.programId,
+          payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [player],
+      });
+
+      const r = await program.account.lotteryRound.fetch(round);
+      expect(r.winner).to.equal(0);
+    });
   });
@@ -13,9 +13,10 @@ import {
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
   SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
 } from "@solana/web3.js";
 import { Lottery } from "../../target/types/lottery";
-import { airdropUsers, assertFail } from "../helpers";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 export const testParticipate = (provider: Provider) =>
@@ -29,6 +30,7 @@ export const testParticipate = (provider: Provider) =>
     let lotteryKey: PublicKey, treasury: PublicKey, playerAccount: PublicKey;
 
     const startingAmount = new BN(10 ** 10);
+    const revealWindow = new BN(100);
     const period = new BN(5);
 
     beforeEach(async () => {
@@ -83,7 +85,7 @@ export const testParticipate = (provider: Provider) =>
 
       const start = Math.round(Date.now() / 1000) - 6;
 
-      await program.rpc.initializeLottery(bumps, period, new BN(start), {
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
         accounts: {
           lotteryKey: lotteryKey,
           lottery: lotteryAddress,
@@ -121,19 +123,31 @@ export const testParticipate = (provider: Provider) =>
         [owner]
       );
 
-      await program.rpc.newLotteryRound(nextRoundBump, {
+      await program.rpc.newLotteryRound(nextRoundBump, null, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: nextRound,
           oldLotteryRound: round,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
         },
         signers: [player],
       });
+
+      // Players can only enter the round once its secret is committed
+      await program.rpc.commitLotteryRound(generateSecret().commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: nextRound,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
     });
 
     it("Participates in the lottery", async () => {
@@ -164,6 +178,11 @@ export const testParticipate = (provider: Provider) =>
         .fill(10 ** 9)
         .map((e) => new BN(e));
 
+      let listener: number;
+      const participated = new Promise<any>((resolve) => {
+        listener = program.addEventListener("Participated", resolve);
+      });
+
       await program.rpc.participate(participationBump, spendings, {
         accounts: {
           lottery: lotteryAddress,
@@ -192,6 +211,12 @@ export const testParticipate = (provider: Provider) =>
       expect(p.spendings.map((e) => e.toString()).toString()).to.equal(
         spendings.map((e) => e.toString()).toString()
       );
+
+      const event = await participated;
+      await program.removeEventListener(listener);
+      expect(event.player.toString()).to.equal(player.publicKey.toString());
+      expect(event.index.toString()).to.equal(new BN(1).toString());
+      expect(event.amount.toString()).to.equal(new BN(8 * 10 ** 9).toString());
     });
 
     it("Fails to participate in expired lottery", async () => {
@@ -250,7 +275,7 @@ export const testParticipate = (provider: Provider) =>
         [
           Buffer.from("round"),
           lotteryKey.toBuffer(),
-          new BN(0).toBuffer("le", 8),
+          new BN(1).toBuffer("le", 8),
         ],
         program.programId
       );
@@ -259,7 +284,7 @@ export const testParticipate = (provider: Provider) =>
           [
             Buffer.from("participation"),
             lotteryKey.toBuffer(),
-            new BN(0).toBuffer("le", 8),
+            new BN(1).toBuffer("le", 8),
             player.publicKey.toBytes(),
           ],
           program.programId
@@ -287,4 +312,129 @@ export const testParticipate = (provider: Provider) =>
         })
       );
     });
+
+    it("Can't record spendings that overflow", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      const [participation, participationBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("participation"),
+            lotteryKey.toBuffer(),
+            new BN(1).toBuffer("le", 8),
+            player.publicKey.toBytes(),
+          ],
+          program.programId
+        );
+
+      // The total would wrap to a single token
+      const spendings = Array(8)
+        .fill(0)
+        .map((e) => new BN(e));
+      spendings[0] = new BN(2).pow(new BN(64)).subn(1);
+      spendings[1] = new BN(2);
+
+      await assertFail(
+        program.rpc.participate(participationBump, spendings, {
+          accounts: {
+            lottery: lotteryAddress,
+            lotteryRound: round,
+            participation: participation,
+            player: player.publicKey,
+            playerAccount: playerAccount,
+            treasury: treasury,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [player],
+        })
+      );
+    });
+
+    it("Spends tokens owned by an authority on behalf of the player", async () => {
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(1).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      const [participation, participationBump] =
+        await PublicKey.findProgramAddress(
+          [
+            Buffer.from("participation"),
+            lotteryKey.toBuffer(),
+            new BN(1).toBuffer("le", 8),
+            player.publicKey.toBytes(),
+          ],
+          program.programId
+        );
+
+      const authority = Keypair.generate();
+      const authorityAccount = await mintRewards.createAccount(
+        authority.publicKey
+      );
+      await mintRewards.mintTo(
+        authorityAccount,
+        owner,
+        [],
+        startingAmount.toNumber()
+      );
+
+      const spendings = Array(8)
+        .fill(10 ** 9)
+        .map((e) => new BN(e));
+      const accounts = (authority: PublicKey, playerAccount: PublicKey) => ({
+        lottery: lotteryAddress,
+        lotteryRound: round,
+        participation: participation,
+        player: player.publicKey,
+        authority: authority,
+        playerAccount: playerAccount,
+        treasury: treasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      });
+
+      // The authority must own the spent tokens
+      await assertFail(
+        program.rpc.participateFor(participationBump, spendings, {
+          accounts: accounts(authority.publicKey, playerAccount),
+          signers: [player, authority],
+        })
+      );
+
+      await program.rpc.participateFor(participationBump, spendings, {
+        accounts: accounts(authority.publicKey, authorityAccount),
+        signers: [player, authority],
+      });
+
+      const p = await program.account.lotteryParticipation.fetch(participation);
+      expect(p.player.toString()).to.equal(player.publicKey.toString());
+      expect(p.spendings.map((e) => e.toString()).toString()).to.equal(
+        spendings.map((e) => e.toString()).toString()
+      );
+      expect(
+        (await mintRewards.getAccountInfo(authorityAccount)).amount.toString()
+      ).to.equal(startingAmount.sub(new BN(8 * 10 ** 9)).toString());
+    });
   });
@@ -0,0 +1,380 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  buildLeaves,
+  faction,
+  lockTiers,
+  merkleCollection,
+  findAttributesAddress,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testRefreshAnimal = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Refresh the rarity of staked animals", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 4;
+    const index = 1;
+    let mintRewards: Token, mints: Token[], tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+    let animal: PublicKey;
+
+    // The collection re-scored with the rarities in reverse order
+    const rescore = () =>
+      new MerkleTree(
+        buildLeaves(
+          mints.map((mint, i) => ({
+            mint: mint.publicKey,
+            rarity: n - 1 - i,
+            faction: (i + 1) % 8,
+          }))
+        )
+      );
+
+    const setRoot = async (newTree: MerkleTree) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      await program.rpc.setJungle(
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        newTree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [owner],
+        }
+      );
+    };
+
+    const refreshAnimal = async (
+      proof: Buffer[] | number[][],
+      rarity: number,
+      factionId: number
+    ) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewardsAccount] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const stakerAccount = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+
+      // Anyone can refresh an animal, here the provider's wallet
+      await program.rpc.refreshAnimal(proof, new BN(rarity), faction(factionId), {
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          attributes: await findAttributesAddress(
+            jungleKey,
+            mints[index].publicKey,
+            program.programId
+          ),
+          stakerAccount: stakerAccount,
+          rewardsAccount: rewardsAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      });
+      return stakerAccount;
+    };
+
+    const stakeAnimal = async (index: number) => {
+      const mint = mints[index];
+      const stakerAccount = (
+        await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        faction(index % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mint.publicKey,
+            stakerAccount: stakerAccount,
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mint.publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return animal;
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      tree = nfts.tree;
+      await Promise.all(
+        mints.map(async (mint) => {
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          const stakerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, stakerAccount, owner, [], 1);
+        })
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+      await mintRewards.mintTo(rewards, owner, [], 10 ** 14);
+
+      animal = await stakeAnimal(index);
+    });
+
+    it("Refreshes an animal after the root changed", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const newTree = rescore();
+      await setRoot(newTree);
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      const before = await program.account.animal.fetch(animal);
+      expect(j.rootVersion.toNumber()).to.equal(1);
+      expect(before.rootVersion.toNumber()).to.equal(0);
+      expect(before.rarity.toNumber()).to.equal(index);
+
+      const stakerAccount = await refreshAnimal(
+        newTree.getProofArray(index),
+        n - 1 - index,
+        (index + 1) % 8
+      );
+
+      const after = await program.account.animal.fetch(animal);
+      const rewards = await mintRewards.getAccountInfo(stakerAccount);
+      expect(after.rootVersion.toNumber()).to.equal(1);
+      expect(after.rarity.toNumber()).to.equal(n - 1 - index);
+      expect(after.faction).to.deep.equal(faction((index + 1) % 8));
+      expect(after.weight.gt(before.weight)).to.equal(true);
+      // The animal moved to its new faction
+      const stats = (await program.account.jungle.fetch(jungleAddress))
+        .factionStats;
+      expect(stats[index % 8].animals.toNumber()).to.equal(0);
+      expect(stats[(index + 1) % 8].animals.toNumber()).to.equal(1);
+      expect(stats[(index + 1) % 8].weight.toString()).to.equal(
+        after.weight.toString()
+      );
+      // The rewards earned at the old rarity are paid
+      expect(rewards.amount.toNumber()).to.be.above(0);
+    });
+
+    it("Can't refresh with a proof against the previous root", async () => {
+      await setRoot(rescore());
+
+      await assertFail(
+        refreshAnimal(tree.getProofArray(index), index, index % 8)
+      );
+    });
+
+    it("Can't refresh an animal that isn't stale", async () => {
+      await assertFail(
+        refreshAnimal(tree.getProofArray(index), index, index % 8)
+      );
+    });
+
+    it("Keeps the registered attributes of the mint", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [attributes, attributesBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("attributes", "utf8"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      await program.rpc.registerAttributes(
+        [
+          {
+            mint: mints[index].publicKey,
+            bump: attributesBump,
+            rarity: new BN(2),
+            faction: faction(6),
+          },
+        ],
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: owner.publicKey,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          remainingAccounts: [
+            { pubkey: attributes, isWritable: true, isSigner: false },
+          ],
+          signers: [owner],
+        }
+      );
+
+      const newTree = rescore();
+      await setRoot(newTree);
+
+      // The leaf of the new root can't override the registry
+      await refreshAnimal(
+        newTree.getProofArray(index),
+        n - 1 - index,
+        (index + 1) % 8
+      );
+
+      const after = await program.account.animal.fetch(animal);
+      expect(after.rootVersion.toNumber()).to.equal(1);
+      expect(after.rarity.toNumber()).to.equal(2);
+      expect(after.faction).to.deep.equal(faction(6));
+    });
+
+    it("Keeps the root version when the root doesn't change", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      await setRoot(tree);
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      expect(j.rootVersion.toNumber()).to.equal(0);
+    });
+  });
@@ -0,0 +1,284 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import { airdropUsers, assertFail, merkleCollection, faction } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testRegisterAttributes = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Register the attributes of animals", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 4;
+    let mints: Token[], tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+
+    const findAttributes = (index: number) =>
+      PublicKey.findProgramAddress(
+        [
+          Buffer.from("attributes", "utf8"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    const registerAttributes = async (
+      entries: { index: number; rarity: number; faction: number }[],
+      signer: Keypair = owner
+    ) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const addresses = await Promise.all(
+        entries.map((entry) => findAttributes(entry.index))
+      );
+
+      await program.rpc.registerAttributes(
+        entries.map((entry, i) => ({
+          mint: mints[entry.index].publicKey,
+          bump: addresses[i][1],
+          rarity: new BN(entry.rarity),
+          faction: faction(entry.faction),
+        })),
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: signer.publicKey,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          remainingAccounts: addresses.map(([address]) => ({
+            pubkey: address,
+            isWritable: true,
+            isSigner: false,
+          })),
+          signers: [signer],
+        }
+      );
+    };
+
+    const stakeAnimal = async (
+      index: number,
+      proof: Buffer[] | number[][],
+      rarity: number,
+      factionId: number,
+      attributesAccount?: PublicKey
+    ) => {
+      const mint = mints[index];
+      const stakerAccount = (
+        await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mint.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [attributes] = await findAttributes(index);
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        proof,
+        new BN(rarity),
+        faction(factionId),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mint.publicKey,
+            stakerAccount: stakerAccount,
+            metadata: SystemProgram.programId,
+            attributes: attributesAccount ?? attributes,
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return animal;
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      const mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      tree = nfts.tree;
+      await Promise.all(
+        mints.map(async (mint) => {
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          const stakerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, stakerAccount, owner, [], 1);
+        })
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+    });
+
+    it("Registers and overwrites attributes", async () => {
+      await registerAttributes([
+        { index: 0, rarity: 3, faction: 5 },
+        { index: 1, rarity: 1, faction: 2 },
+      ]);
+      await registerAttributes([{ index: 1, rarity: 4, faction: 7 }]);
+
+      const [attributes0] = await findAttributes(0);
+      const [attributes1] = await findAttributes(1);
+      const a0 = await program.account.animalAttributes.fetch(attributes0);
+      const a1 = await program.account.animalAttributes.fetch(attributes1);
+      expect(a0.mint.toString()).to.equal(mints[0].publicKey.toString());
+      expect(a0.rarity.toNumber()).to.equal(3);
+      expect(a0.faction).to.deep.equal(faction(5));
+      expect(a1.rarity.toNumber()).to.equal(4);
+      expect(a1.faction).to.deep.equal(faction(7));
+    });
+
+    it("Only the owner can register attributes", async () => {
+      await assertFail(
+        registerAttributes([{ index: 0, rarity: 3, faction: 5 }], staker)
+      );
+    });
+
+    it("Stakes an animal with its registered attributes", async () => {
+      await registerAttributes([{ index: 0, rarity: 3, faction: 5 }]);
+
+      // No proof is needed and the arguments are ignored
+      const animal = await stakeAnimal(0, [], 0, 0);
+      const a = await program.account.animal.fetch(animal);
+      expect(a.rarity.toNumber()).to.equal(3);
+      expect(a.faction).to.deep.equal(faction(5));
+    });
+
+    it("Falls back to the Merkle proof for unregistered animals", async () => {
+      await assertFail(stakeAnimal(1, [], 0, 0));
+
+      const animal = await stakeAnimal(1, tree.getProofArray(1), 1, 1);
+      const a = await program.account.animal.fetch(animal);
+      expect(a.rarity.toNumber()).to.equal(1);
+    });
+
+    it("Can't skip the registry with another account", async () => {
+      await registerAttributes([{ index: 1, rarity: 0, faction: 1 }]);
+
+      // The Merkle leaf of the animal has a higher rarity than its registered attributes
+      await assertFail(
+        stakeAnimal(1, tree.getProofArray(1), 1, 1, SystemProgram.programId)
+      );
+      await assertFail(
+        stakeAnimal(
+          1,
+          tree.getProofArray(1),
+          1,
+          1,
+          (await findAttributes(0))[0]
+        )
+      );
+
+      const animal = await stakeAnimal(1, [], 0, 0);
+      const a = await program.account.animal.fetch(animal);
+      expect(a.rarity.toNumber()).to.equal(0);
+    });
+  });
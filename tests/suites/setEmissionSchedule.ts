@@ -0,0 +1,178 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  emissionSchedule,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+export const testSetEmissionSchedule = (
+  state: {
+    owner: Keypair;
+    staker: Keypair;
+    jungleKey: PublicKey;
+    mintRewards: Token;
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Setting the emission schedule", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    let jungleKey: PublicKey, owner: Keypair, stranger: Keypair;
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      stranger = Keypair.generate();
+      await airdropUsers([owner, stranger], provider);
+
+      const mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const { tree } = await merkleCollection(owner, 3, provider);
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(3),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+    });
+
+    it("Sets a halving schedule", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+
+      // Halve the emissions every 52 weeks
+      const year = 52 * 604800;
+      const base = state.baseWeeklyEmissions.toNumber();
+      const breakpoints = [1, 2, 3].map((i) => [
+        state.start.toNumber() + i * year,
+        Math.floor(base / 2 ** i),
+      ]);
+
+      await program.rpc.setEmissionSchedule(emissionSchedule(breakpoints), {
+        accounts: {
+          jungle: jungleAddress,
+          owner: owner.publicKey,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        signers: [owner],
+      });
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      breakpoints.forEach(([start, weeklyEmissions], i) => {
+        expect(j.emissionSchedule[i].start.toString()).to.equal(
+          start.toString()
+        );
+        expect(j.emissionSchedule[i].weeklyEmissions.toString()).to.equal(
+          weeklyEmissions.toString()
+        );
+      });
+      expect(j.emissionSchedule[3].start.toString()).to.equal("0");
+    });
+
+    it("Fails with an unsorted schedule", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+
+      await assertFail(
+        program.rpc.setEmissionSchedule(
+          emissionSchedule([
+            [state.start.toNumber() + 2000, 10],
+            [state.start.toNumber() + 1000, 20],
+          ]),
+          {
+            accounts: {
+              jungle: jungleAddress,
+              owner: owner.publicKey,
+              clock: SYSVAR_CLOCK_PUBKEY,
+            },
+            signers: [owner],
+          }
+        )
+      );
+    });
+
+    it("Fails when called by an outsider", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+
+      await assertFail(
+        program.rpc.setEmissionSchedule(emissionSchedule(), {
+          accounts: {
+            jungle: jungleAddress,
+            owner: stranger.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [stranger],
+        })
+      );
+    });
+  });
@@ -11,9 +11,10 @@ import {
   Keypair,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { Jungle } from "../../target/types/jungle";
-import { airdropUsers, assertFail, merkleCollection } from "../helpers";
+import { airdropUsers, assertFail, merkleCollection, lockTiers } from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { MerkleTree } from "../helpers/merkleTree";
 
@@ -53,7 +54,6 @@ export const testSetJungle = (
     });
 
     it("Reset the jungle", async () => {
-      const newOwner = Keypair.generate();
       const newMaximumMultiplier = new BN(100000);
       const newWeekly = new BN(100000);
 
@@ -71,11 +71,13 @@ export const testSetJungle = (
         newWeekly,
         state.start,
         tree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
         {
           accounts: {
             jungle: jungleAddress,
             owner: state.owner.publicKey,
-            newOwner: newOwner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
           },
           signers: [state.owner],
         }
@@ -83,7 +85,7 @@ export const testSetJungle = (
 
       const s = await program.account.jungle.fetch(jungleAddress);
 
-      expect(s.owner.toString()).to.equal(newOwner.publicKey.toString());
+      expect(s.owner.toString()).to.equal(state.owner.publicKey.toString());
       expect(s.maximumRarity.toString()).to.equal(newMaximumRarity.toString());
       expect(s.maximumRarityMultiplier.toString()).to.equal(
         newMaximumMultiplier.toString()
@@ -99,13 +101,15 @@ export const testSetJungle = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
         {
           accounts: {
             jungle: jungleAddress,
-            owner: newOwner.publicKey,
-            newOwner: state.owner.publicKey,
+            owner: state.owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
           },
-          signers: [newOwner],
+          signers: [state.owner],
         }
       );
     });
@@ -128,15 +132,44 @@ export const testSetJungle = (
           newMaximumMultiplier,
           newWeekly,
           tree.getRootArray(),
+          { deposit: {} },
+          lockTiers(),
           {
             accounts: {
               jungle: jungleAddress,
               owner: newOwner.publicKey,
-              newOwner: newOwner.publicKey,
+              clock: SYSVAR_CLOCK_PUBKEY,
             },
             signers: [newOwner],
           }
         )
       );
     });
+
+    it("Refuses a null maximum rarity", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), state.jungleKey.toBuffer()],
+        program.programId
+      );
+
+      await assertFail(
+        program.rpc.setJungle(
+          new BN(0),
+          state.maxMultiplier,
+          state.baseWeeklyEmissions,
+          state.start,
+          tree.getRootArray(),
+          { deposit: {} },
+          lockTiers(),
+          {
+            accounts: {
+              jungle: jungleAddress,
+              owner: state.owner.publicKey,
+              clock: SYSVAR_CLOCK_PUBKEY,
+            },
+            signers: [state.owner],
+          }
+        )
+      );
+    });
   });
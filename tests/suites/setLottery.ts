@@ -32,6 +32,7 @@ export const testSetLottery = (
 
     const startingAmount = new BN(10 ** 10);
     const period = new BN(5)
+    const revealWindow = new BN(100)
 
     before(async () => {
       owner = Keypair.generate();
@@ -85,6 +86,7 @@ export const testSetLottery = (
         bumps,
         period,
         new BN(start),
+        revealWindow,
         {
           accounts: {
             lotteryKey: lotteryKey,
@@ -129,6 +131,7 @@ export const testSetLottery = (
         bumps2,
         period,
         new BN(start),
+        revealWindow,
         {
           accounts: {
             lotteryKey: otherLotteryKey,
@@ -160,13 +163,15 @@ export const testSetLottery = (
       const newMint = Keypair.generate().publicKey
       const newTreasury = Keypair.generate().publicKey
       const newPeriod = new BN(10000)
+      const newRevealWindow = new BN(600)
 
       await program.rpc.setLottery(
         new BN(start+1),
-        player.publicKey,
         newMint,
         newTreasury,
         newPeriod,
+        newRevealWindow,
+        new PublicKey(0),
         {
           accounts: {
             lottery: lotteryAddress,
@@ -181,10 +186,11 @@ export const testSetLottery = (
       console.log(owner.publicKey.toString(), player.publicKey.toString())
 
       expect(s.key.toString()).to.equal(lotteryKey.toString());
-      expect(s.owner.toString()).to.equal(player.publicKey.toString());
+      expect(s.owner.toString()).to.equal(owner.publicKey.toString());
       expect(s.mint.toString()).to.equal(newMint.toString());
       expect(s.escrow.toString()).to.equal(escrow.toString());
       expect(s.period.toString()).to.equal(newPeriod.toString());
+      expect(s.revealWindow.toString()).to.equal(newRevealWindow.toString());
       expect(s.treasury.toString()).to.equal(newTreasury.toString());
       expect(s.lastTimestamp.toNumber()).to.equal(start+1);
     });
@@ -199,13 +205,15 @@ export const testSetLottery = (
       const newMint = Keypair.generate().publicKey
       const newTreasury = Keypair.generate().publicKey
       const newPeriod = new BN(10000)
+      const newRevealWindow = new BN(600)
 
       await assertFail(program.rpc.setLottery(
         new BN(start+1),
-        player.publicKey,
         newMint,
         newTreasury,
         newPeriod,
+        newRevealWindow,
+        new PublicKey(0),
         {
           accounts: {
             lottery: lotteryAddress,
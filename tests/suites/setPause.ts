@@ -0,0 +1,328 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  findAttributesAddress,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+const PAUSE_STAKE = 1;
+const PAUSE_CLAIM = 2;
+const PAUSE_WITHDRAW = 4;
+
+export const testSetPause = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Pause the jungle", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 5;
+    const indexStaked = 1;
+    const indexUnstaked = 3;
+
+    let mintRewards: Token, mints: Token[], accounts: PublicKey[];
+    let tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, guardian: Keypair, staker: Keypair;
+
+    const findAddresses = async (index: number) => {
+      const [jungle] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      return { jungle, escrow, rewards, animal, animalBump, deposit, depositBump };
+    };
+
+    const stakeAnimal = async (index: number) => {
+      const a = await findAddresses(index);
+      await program.rpc.stakeAnimal(
+        { animal: a.animalBump, deposit: a.depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        new BN(0),
+        0,
+        {
+          accounts: {
+            jungle: a.jungle,
+            escrow: a.escrow,
+            animal: a.animal,
+            staker: staker.publicKey,
+            mint: mints[index].publicKey,
+            stakerAccount: accounts[index],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[index].publicKey,
+              program.programId
+            ),
+            depositAccount: a.deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+    };
+
+    const setPause = async (paused: number, authority: Keypair) =>
+      await program.rpc.setPause(paused, {
+        accounts: {
+          jungle: (await findAddresses(0)).jungle,
+          authority: authority.publicKey,
+        },
+        signers: [authority],
+      });
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      guardian = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, guardian, staker], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      tree = nfts.tree;
+      accounts = await Promise.all(
+        mints.map(async (mint) => {
+          const account = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, account, owner, [], 1);
+          return account;
+        })
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(n),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+      await mintRewards.mintTo(rewards, owner, [], 10 ** 14);
+
+      await program.rpc.setGuardian({
+        accounts: {
+          jungle: jungleAddress,
+          owner: owner.publicKey,
+          guardian: guardian.publicKey,
+        },
+        signers: [owner],
+      });
+
+      await stakeAnimal(indexStaked);
+    });
+
+    it("Blocks the paused operations but not unstaking", async () => {
+      const a = await findAddresses(indexStaked);
+
+      await setPause(PAUSE_STAKE | PAUSE_CLAIM | PAUSE_WITHDRAW, guardian);
+      const j = await program.account.jungle.fetch(a.jungle);
+      expect(j.paused).to.equal(PAUSE_STAKE | PAUSE_CLAIM | PAUSE_WITHDRAW);
+
+      await assertFail(stakeAnimal(indexUnstaked));
+
+      const stakerRewards = await mintRewards.getOrCreateAssociatedAccountInfo(
+        staker.publicKey
+      );
+      await assertFail(
+        program.rpc.claimStaking({
+          accounts: {
+            jungle: a.jungle,
+            escrow: a.escrow,
+            animal: a.animal,
+            staker: staker.publicKey,
+            authority: staker.publicKey,
+            profile: SystemProgram.programId,
+            mint: mintRewards.publicKey,
+            stakerAccount: stakerRewards.address,
+            rewardsAccount: a.rewards,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        })
+      );
+
+      // Withdrawals can still be queued but not executed
+      const ownerRewards = await mintRewards.getOrCreateAssociatedAccountInfo(
+        owner.publicKey
+      );
+      const [withdrawal, withdrawalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("withdrawal"),
+          jungleKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      await program.rpc.queueWithdrawal(withdrawalBump, new BN(1), {
+        accounts: {
+          jungle: a.jungle,
+          withdrawal: withdrawal,
+          owner: owner.publicKey,
+          destination: ownerRewards.address,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [owner],
+      });
+      await assertFail(
+        program.rpc.executeWithdrawal({
+          accounts: {
+            jungle: a.jungle,
+            escrow: a.escrow,
+            rewardsAccount: a.rewards,
+            withdrawal: withdrawal,
+            owner: owner.publicKey,
+            destination: ownerRewards.address,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [owner],
+        })
+      );
+
+      await program.rpc.unstakeAnimal({
+        accounts: {
+          jungle: a.jungle,
+          escrow: a.escrow,
+          animal: a.animal,
+          staker: staker.publicKey,
+          mint: mints[indexStaked].publicKey,
+          stakerAccount: accounts[indexStaked],
+          depositAccount: a.deposit,
+          edition: SystemProgram.programId,
+          tokenMetadataProgram: SystemProgram.programId,
+          rewardsAccount: SystemProgram.programId,
+          stakerRewardsAccount: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        signers: [staker],
+      });
+      expect(
+        (await mints[indexStaked].getAccountInfo(accounts[indexStaked])).amount.toNumber()
+      ).to.equal(1);
+    });
+
+    it("Resumes the operations", async () => {
+      await setPause(PAUSE_STAKE, guardian);
+      await assertFail(stakeAnimal(indexUnstaked));
+
+      // Only the owner can lift a pause
+      await assertFail(setPause(0, guardian));
+      await setPause(0, owner);
+      await stakeAnimal(indexUnstaked);
+    });
+
+    it("Rejects unknown flags", async () => {
+      await assertFail(setPause(1 << 3, owner));
+      await assertFail(setPause(1 << 7, guardian));
+    });
+
+    it("Fails when called by an outsider", async () => {
+      await assertFail(setPause(PAUSE_STAKE, staker));
+    });
+  });
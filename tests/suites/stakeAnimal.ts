@@ -14,7 +14,14 @@ import {
   SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { Jungle } from "../../target/types/jungle";
-import { airdropUsers, assertFail, merkleCollection } from "../helpers";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  lockTiers,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { MerkleTree } from "../helpers/merkleTree";
 
@@ -85,11 +92,13 @@ export const testStakeAnimal = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
         {
           accounts: {
             jungle: jungleAddress,
             owner: state.owner.publicKey,
-            newOwner: state.owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
           },
           signers: [state.owner],
         }
@@ -108,6 +117,7 @@ export const testStakeAnimal = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          state.jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -115,6 +125,7 @@ export const testStakeAnimal = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          state.jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -125,11 +136,17 @@ export const testStakeAnimal = (
         deposit: depositBump,
       };
 
+      let listener: number;
+      const staked = new Promise<any>((resolve) => {
+        listener = program.addEventListener("AnimalStaked", resolve);
+      });
+
       await program.rpc.stakeAnimal(
         bumps,
         tree.getProofArray(indexStaked),
         new BN(indexStaked),
-        new BN(indexStaked % 8),
+        faction(indexStaked % 8),
+        0,
         {
           accounts: {
             jungle: jungleAddress,
@@ -138,6 +155,12 @@ export const testStakeAnimal = (
             staker: holders[indexStaked].publicKey,
             mint: mints[indexStaked].publicKey,
             stakerAccount: accounts[indexStaked],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              state.jungleKey,
+              mints[indexStaked].publicKey,
+              program.programId
+            ),
             depositAccount: deposit,
             tokenProgram: TOKEN_PROGRAM_ID,
             clock: SYSVAR_CLOCK_PUBKEY,
@@ -154,16 +177,44 @@ export const testStakeAnimal = (
       const timeAfter = Date.now() / 1000;
 
       expect(j.animalsStaked.toString()).to.equal(new BN(1).toString());
+      expect(j.totalWeight.toString()).to.equal(a.weight.toString());
+      expect(
+        j.factionStats[indexStaked % 8].animals.toString()
+      ).to.equal(new BN(1).toString());
+      expect(j.factionStats[indexStaked % 8].weight.toString()).to.equal(
+        a.weight.toString()
+      );
+      expect(a.weight.toString()).to.equal(
+        new BN(10000)
+          .add(
+            state.maxMultiplier
+              .sub(new BN(10000))
+              .mul(new BN(indexStaked))
+              .div(maxRarity)
+          )
+          .toString()
+      );
       expect(a.staker.toString()).to.equal(
         holders[indexStaked].publicKey.toString()
       );
       expect(a.mint.toString()).to.equal(
         mints[indexStaked].publicKey.toString()
       );
-      expect(a.faction.toString()).to.equal(new BN(indexStaked).toString());
+      expect(a.faction).to.deep.equal(faction(indexStaked));
       expect(a.rarity.toString()).to.equal(new BN(indexStaked).toString());
       expect(a.lastClaim.lte(new BN(timeAfter))).to.equal(true);
       expect(a.lastClaim.gt(new BN(0))).to.equal(true);
+
+      const event = await staked;
+      await program.removeEventListener(listener);
+      expect(event.mint.toString()).to.equal(
+        mints[indexStaked].publicKey.toString()
+      );
+      expect(event.staker.toString()).to.equal(
+        holders[indexStaked].publicKey.toString()
+      );
+      expect(event.rarity.toString()).to.equal(new BN(indexStaked).toString());
+      expect(event.ts.toString()).to.equal(a.lastClaim.toString());
     });
 
     it("Fails when it's too early", async () => {
@@ -178,11 +229,13 @@ export const testStakeAnimal = (
         state.baseWeeklyEmissions,
         new BN(Date.now() + 1000000),
         tree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
         {
           accounts: {
             jungle: jungleAddress,
             owner: state.owner.publicKey,
-            newOwner: state.owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
           },
           signers: [state.owner],
         }
@@ -195,6 +248,7 @@ export const testStakeAnimal = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          state.jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -202,6 +256,7 @@ export const testStakeAnimal = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          state.jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -216,7 +271,8 @@ export const testStakeAnimal = (
         bumps,
         tree.getProofArray(indexStaked),
         new BN(indexStaked),
-        new BN(indexStaked % 8),
+        faction(indexStaked % 8),
+        0,
         {
           accounts: {
             jungle: jungleAddress,
@@ -225,6 +281,12 @@ export const testStakeAnimal = (
             staker: holders[indexStaked].publicKey,
             mint: mints[indexStaked].publicKey,
             stakerAccount: accounts[indexStaked],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              state.jungleKey,
+              mints[indexStaked].publicKey,
+              program.programId
+            ),
             depositAccount: deposit,
             tokenProgram: TOKEN_PROGRAM_ID,
             clock: SYSVAR_CLOCK_PUBKEY,
@@ -242,11 +304,13 @@ export const testStakeAnimal = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        { deposit: {} },
+        lockTiers(),
         {
           accounts: {
             jungle: jungleAddress,
             owner: state.owner.publicKey,
-            newOwner: state.owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
           },
           signers: [state.owner],
         }
@@ -265,6 +329,7 @@ export const testStakeAnimal = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          state.jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -272,6 +337,7 @@ export const testStakeAnimal = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          state.jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -292,6 +358,7 @@ export const testStakeAnimal = (
           tree.getProofArray(indexStaked),
           new BN(indexStaked),
           new BN(indexStaked),
+          0,
           {
             accounts: {
               jungle: jungleAddress,
@@ -300,6 +367,12 @@ export const testStakeAnimal = (
               staker: stranger.publicKey,
               mint: mints[indexStaked].publicKey,
               stakerAccount: stakerAccount.address,
+              metadata: SystemProgram.programId,
+              attributes: await findAttributesAddress(
+                state.jungleKey,
+                mints[indexStaked].publicKey,
+                program.programId
+              ),
               depositAccount: deposit,
               tokenProgram: TOKEN_PROGRAM_ID,
               clock: SYSVAR_CLOCK_PUBKEY,
@@ -0,0 +1,324 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  buildLeaves,
+  lockTiers,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testStakeAnimalInWallet = (
+  state: {
+    owner: Keypair;
+    staker: Keypair;
+    jungleKey: PublicKey;
+    mintRewards: Token;
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Stake an animal in the wallet", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 4;
+    let mintRewards: Token, mints: Token[], accounts: PublicKey[];
+    let tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+
+    const maxRarity = new BN(n);
+    const indexStaked = 2;
+
+    const findAddresses = async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      return { jungleAddress, escrow, animal, animalBump, deposit };
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      // The escrow is the freeze authority of the collection
+      mints = await Promise.all(
+        Array(n)
+          .fill(0)
+          .map(() =>
+            Token.createMint(
+              provider.connection,
+              owner,
+              owner.publicKey,
+              escrow,
+              0,
+              TOKEN_PROGRAM_ID
+            )
+          )
+      );
+      accounts = await Promise.all(
+        mints.map(async (mint) => {
+          const account = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          await mint.mintTo(account, owner, [], 1);
+          return account;
+        })
+      );
+      tree = new MerkleTree(
+        buildLeaves(
+          mints.map((e, i) => ({
+            mint: e.publicKey,
+            rarity: i,
+            faction: i % 8,
+          }))
+        )
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        maxRarity,
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+
+      await program.rpc.setJungle(
+        maxRarity,
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        { freezeAuthority: {} },
+        lockTiers(),
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [owner],
+        }
+      );
+    });
+
+    it("Freezes the token in the wallet and thaws it when unstaking", async () => {
+      const { jungleAddress, escrow, animal, animalBump, deposit } =
+        await findAddresses();
+
+      await program.rpc.stakeAnimalInWallet(
+        animalBump,
+        tree.getProofArray(indexStaked),
+        new BN(indexStaked),
+        faction(indexStaked % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mints[indexStaked].publicKey,
+            stakerAccount: accounts[indexStaked],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[indexStaked].publicKey,
+              program.programId
+            ),
+            edition: SystemProgram.programId,
+            tokenMetadataProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+
+      const a = await program.account.animal.fetch(animal);
+      expect(a.mode).to.deep.equal({ freezeAuthority: {} });
+
+      // The token stays in the wallet but can't move
+      let stakerAccount = await mints[indexStaked].getAccountInfo(
+        accounts[indexStaked]
+      );
+      expect(stakerAccount.amount.toString()).to.equal("1");
+      expect(stakerAccount.isFrozen).to.equal(true);
+      expect(stakerAccount.delegate.toString()).to.equal(escrow.toString());
+
+      const otherAccount = (
+        await mints[indexStaked].getOrCreateAssociatedAccountInfo(
+          owner.publicKey
+        )
+      ).address;
+      await assertFail(
+        mints[indexStaked].transfer(
+          accounts[indexStaked],
+          otherAccount,
+          staker,
+          [],
+          1
+        )
+      );
+
+      await program.rpc.unstakeAnimal({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: staker.publicKey,
+          mint: mints[indexStaked].publicKey,
+          stakerAccount: accounts[indexStaked],
+          depositAccount: deposit,
+          edition: SystemProgram.programId,
+          tokenMetadataProgram: SystemProgram.programId,
+          rewardsAccount: SystemProgram.programId,
+          stakerRewardsAccount: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        signers: [staker],
+      });
+
+      stakerAccount = await mints[indexStaked].getAccountInfo(
+        accounts[indexStaked]
+      );
+      expect(stakerAccount.isFrozen).to.equal(false);
+      expect(stakerAccount.delegate).to.equal(null);
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      expect(j.animalsStaked.toString()).to.equal("0");
+    });
+
+    it("Can't stake with the deposit account in this mode", async () => {
+      const { jungleAddress, escrow, animal, animalBump, deposit } =
+        await findAddresses();
+      const [, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await assertFail(
+        program.rpc.stakeAnimal(
+          { animal: animalBump, deposit: depositBump },
+          tree.getProofArray(indexStaked),
+          new BN(indexStaked),
+          faction(indexStaked % 8),
+          0,
+          {
+            accounts: {
+              jungle: jungleAddress,
+              escrow: escrow,
+              animal: animal,
+              staker: staker.publicKey,
+              mint: mints[indexStaked].publicKey,
+              stakerAccount: accounts[indexStaked],
+              metadata: SystemProgram.programId,
+              attributes: await findAttributesAddress(
+                jungleKey,
+                mints[indexStaked].publicKey,
+                program.programId
+              ),
+              depositAccount: deposit,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              clock: SYSVAR_CLOCK_PUBKEY,
+              rent: SYSVAR_RENT_PUBKEY,
+              systemProgram: SystemProgram.programId,
+            },
+            signers: [staker],
+          }
+        )
+      );
+    });
+  });
@@ -0,0 +1,390 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+  Transaction,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import { airdropUsers, assertFail, merkleCollection, faction } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testStakeAnimals = (
+  state: {
+    owner: Keypair;
+    staker: Keypair;
+    jungleKey: PublicKey;
+    mintRewards: Token;
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Stake several animals", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 10;
+    let mintRewards: Token,
+      mints: Token[],
+      accounts: PublicKey[] = Array(n).fill(new PublicKey(0));
+    let tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+
+    const maxRarity = new BN(n);
+    const indexesStaked = [1, 4, 5, 8];
+
+    // The leaves and remaining accounts of the animals to stake
+    const animalsParams = async (indexes: number[]) => {
+      const leaves = [];
+      const remainingAccounts = [];
+      for (const index of indexes) {
+        const [animal, animalBump] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("animal", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const [deposit, depositBump] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("deposit", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const [attributes] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("attributes", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        leaves.push({
+          bumps: { animal: animalBump, deposit: depositBump },
+          index: index,
+          rarity: new BN(index),
+          faction: faction(index % 8),
+          lockTier: 0,
+        });
+        remainingAccounts.push(
+          { pubkey: animal, isWritable: true, isSigner: false },
+          {
+            pubkey: mints[index].publicKey,
+            isWritable: false,
+            isSigner: false,
+          },
+          { pubkey: accounts[index], isWritable: true, isSigner: false },
+          { pubkey: deposit, isWritable: true, isSigner: false },
+          { pubkey: attributes, isWritable: false, isSigner: false }
+        );
+      }
+      return { leaves, remainingAccounts };
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      await Promise.all(
+        mints.map(async (mint, i) => {
+          accounts[i] = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, accounts[i], owner, [], 1);
+        })
+      );
+      tree = nfts.tree;
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        maxRarity,
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+    });
+
+    it("Stakes several animals with one proof", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const { leaves, remainingAccounts } = await animalsParams(indexesStaked);
+
+      // Shared branches are only sent once
+      const proof = tree.getMultiProofArray(indexesStaked);
+      expect(proof.length).to.be.lessThan(
+        indexesStaked
+          .map((index) => tree.getProof(index).length)
+          .reduce((a, b) => a + b)
+      );
+
+      await program.rpc.stakeAnimals(n, proof, leaves, {
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          staker: staker.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        remainingAccounts,
+        signers: [staker],
+      });
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      expect(j.animalsStaked.toString()).to.equal(
+        indexesStaked.length.toString()
+      );
+
+      let totalWeight = new BN(0);
+      for (const index of indexesStaked) {
+        const [animal] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("animal", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const a = await program.account.animal.fetch(animal);
+        expect(a.mint.toString()).to.equal(mints[index].publicKey.toString());
+        expect(a.staker.toString()).to.equal(staker.publicKey.toString());
+        expect(a.rarity.toString()).to.equal(index.toString());
+        expect(a.faction).to.deep.equal(faction(index % 8));
+        totalWeight = totalWeight.add(a.weight);
+
+        const stakerAccount = await mints[index].getAccountInfo(
+          accounts[index]
+        );
+        expect(stakerAccount.amount.toString()).to.equal("0");
+      }
+      expect(j.totalWeight.toString()).to.equal(totalWeight.toString());
+    });
+
+    it("Uses the registered attributes of the mints", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const { leaves, remainingAccounts } = await animalsParams(indexesStaked);
+
+      // The second animal is registered with attributes that differ from its leaf
+      const registered = indexesStaked[1];
+      const attributes = remainingAccounts[5 * 1 + 4].pubkey;
+      const [, attributesBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("attributes", "utf8"),
+          jungleKey.toBuffer(),
+          mints[registered].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      await program.rpc.registerAttributes(
+        [
+          {
+            mint: mints[registered].publicKey,
+            bump: attributesBump,
+            rarity: new BN(2),
+            faction: faction(3),
+          },
+        ],
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: owner.publicKey,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          remainingAccounts: [
+            { pubkey: attributes, isWritable: true, isSigner: false },
+          ],
+          signers: [owner],
+        }
+      );
+
+      // Only the leaves of the mints that aren't registered are proven
+      const proof = tree.getMultiProofArray(
+        indexesStaked.filter((index) => index != registered)
+      );
+      await program.rpc.stakeAnimals(n, proof, leaves, {
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          staker: staker.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        remainingAccounts,
+        signers: [staker],
+      });
+
+      const a = await program.account.animal.fetch(remainingAccounts[5].pubkey);
+      expect(a.mint.toString()).to.equal(mints[registered].publicKey.toString());
+      expect(a.rarity.toString()).to.equal("2");
+      expect(a.faction).to.deep.equal(faction(3));
+    });
+
+    it("Stakes animals whose addresses were pre-funded", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const { leaves, remainingAccounts } = await animalsParams(indexesStaked);
+
+      // Sending lamports to the animal and deposit addresses must not block staking
+      const [animal, , , deposit] = remainingAccounts.map((a) => a.pubkey);
+      await provider.send(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: owner.publicKey,
+            toPubkey: animal,
+            lamports: 1000000,
+          }),
+          SystemProgram.transfer({
+            fromPubkey: owner.publicKey,
+            toPubkey: deposit,
+            lamports: 1000000,
+          })
+        ),
+        [owner]
+      );
+
+      await program.rpc.stakeAnimals(
+        n,
+        tree.getMultiProofArray(indexesStaked),
+        leaves,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            staker: staker.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          remainingAccounts,
+          signers: [staker],
+        }
+      );
+
+      const a = await program.account.animal.fetch(animal);
+      expect(a.staker.toString()).to.equal(staker.publicKey.toString());
+      const d = await mints[indexesStaked[0]].getAccountInfo(deposit);
+      expect(d.amount.toString()).to.equal("1");
+    });
+
+    it("Fails with a wrong leaf", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const { leaves, remainingAccounts } = await animalsParams(indexesStaked);
+      leaves[1].rarity = maxRarity;
+
+      await assertFail(
+        program.rpc.stakeAnimals(
+          n,
+          tree.getMultiProofArray(indexesStaked),
+          leaves,
+          {
+            accounts: {
+              jungle: jungleAddress,
+              escrow: escrow,
+              staker: staker.publicKey,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              clock: SYSVAR_CLOCK_PUBKEY,
+              rent: SYSVAR_RENT_PUBKEY,
+              systemProgram: SystemProgram.programId,
+            },
+            remainingAccounts,
+            signers: [staker],
+          }
+        )
+      );
+    });
+  });
@@ -0,0 +1,252 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import fs from "fs";
+import { Jungle } from "../../target/types/jungle";
+import {
+  airdropUsers,
+  assertFail,
+  buildLeaves,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+const METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+
+// Mints and Metaplex metadata accounts loaded in the local validator by Anchor.toml
+const fixtures = JSON.parse(
+  fs.readFileSync("./tests/fixtures/collection.json").toString()
+);
+const mintAuthority = Keypair.fromSecretKey(
+  Uint8Array.from(
+    JSON.parse(
+      fs.readFileSync("./tests/fixtures/mintAuthority.json").toString()
+    )
+  )
+);
+
+export const testStakeCollection = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Stake tokens of a verified collection", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const names = [
+      "verifiedCollection",
+      "unverifiedCollection",
+      "verifiedCreator",
+      "unverifiedCreator",
+    ];
+    const mints: PublicKey[] = names.map(
+      (name) => new PublicKey(fixtures.mints[name])
+    );
+    const tree = new MerkleTree(
+      buildLeaves(
+        mints.map((mint, i) => ({ mint, rarity: i, faction: i % 8 }))
+      )
+    );
+
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair;
+
+    const setEligibility = async (eligibility: any, collection: PublicKey) => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      await program.rpc.setEligibility(eligibility, collection, {
+        accounts: {
+          jungle: jungleAddress,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+    };
+
+    const stakeAnimal = async (index: number, metadata?: PublicKey) => {
+      const mint = new Token(
+        provider.connection,
+        mints[index],
+        TOKEN_PROGRAM_ID,
+        staker
+      );
+      const stakerAccount = (
+        await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+      ).address;
+      await mint.mintTo(stakerAccount, mintAuthority, [], 1);
+
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("animal"), jungleKey.toBuffer(), mints[index].toBuffer()],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit"),
+          jungleKey.toBuffer(),
+          mints[index].toBuffer(),
+        ],
+        program.programId
+      );
+      const [metadataAddress] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("metadata"),
+          METADATA_PROGRAM_ID.toBuffer(),
+          mints[index].toBuffer(),
+        ],
+        METADATA_PROGRAM_ID
+      );
+
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        faction(index % 8),
+        0,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: staker.publicKey,
+            mint: mints[index],
+            stakerAccount: stakerAccount,
+            metadata: metadata ?? metadataAddress,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[index],
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [staker],
+        }
+      );
+      return animal;
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      await airdropUsers([owner, staker], provider);
+
+      const mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(mints.length),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+    });
+
+    it("Stakes a token of the verified collection", async () => {
+      await setEligibility(
+        { verifiedCollection: {} },
+        new PublicKey(fixtures.collection)
+      );
+
+      const animal = await stakeAnimal(0);
+      const a = await program.account.animal.fetch(animal);
+      expect(a.mint.toString()).to.equal(mints[0].toString());
+
+      // The collection is not verified, or missing
+      await assertFail(stakeAnimal(1));
+      await assertFail(stakeAnimal(2));
+    });
+
+    it("Stakes a token with the verified first creator", async () => {
+      await setEligibility(
+        { verifiedCreator: {} },
+        new PublicKey(fixtures.creator)
+      );
+
+      await stakeAnimal(2);
+      await assertFail(stakeAnimal(3));
+      await assertFail(stakeAnimal(0));
+    });
+
+    it("Refuses metadata accounts not owned by Metaplex", async () => {
+      await setEligibility(
+        { verifiedCollection: {} },
+        new PublicKey(fixtures.collection)
+      );
+
+      await assertFail(stakeAnimal(0, mints[0]));
+    });
+  });
@@ -0,0 +1,149 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import { Lottery } from "../../target/types/lottery";
+import { airdropUsers, assertFail } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+export const testTransferLotteryOwnership = (provider: Provider) =>
+  describe("Transfer the ownership of the lottery", () => {
+    setProvider(provider);
+
+    const program = workspace.Lottery as Program<Lottery>;
+
+    let lotteryKey: PublicKey, owner: Keypair, newOwner: Keypair;
+
+    const period = new BN(1000);
+    const revealWindow = new BN(100);
+
+    const findLottery = async () =>
+      (
+        await PublicKey.findProgramAddress(
+          [Buffer.from("lottery"), lotteryKey.toBuffer()],
+          program.programId
+        )
+      )[0];
+
+    const proposeOwner = async (proposed: PublicKey, signer: Keypair) =>
+      await program.rpc.proposeOwner({
+        accounts: {
+          lottery: await findLottery(),
+          owner: signer.publicKey,
+          newOwner: proposed,
+        },
+        signers: [signer],
+      });
+
+    const acceptOwnership = async (signer: Keypair) =>
+      await program.rpc.acceptOwnership({
+        accounts: {
+          lottery: await findLottery(),
+          pendingOwner: signer.publicKey,
+        },
+        signers: [signer],
+      });
+
+    beforeEach(async () => {
+      lotteryKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      newOwner = Keypair.generate();
+      await airdropUsers([owner, newOwner], provider);
+
+      const mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const treasury = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(owner.publicKey)
+      ).address;
+
+      const [lotteryAddress, lotteryBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("lottery"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), lotteryKey.toBuffer()],
+        program.programId
+      );
+      const [round, roundBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("round"),
+          lotteryKey.toBuffer(),
+          new BN(0).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+
+      const bumps = {
+        lottery: lotteryBump,
+        escrow: escrowBump,
+        round: roundBump,
+      };
+
+      const start = Math.round(Date.now() / 1000);
+
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
+        accounts: {
+          lotteryKey: lotteryKey,
+          lottery: lotteryAddress,
+          lotteryRound: round,
+          escrow: escrow,
+          mint: mintRewards.publicKey,
+          treasury: treasury,
+          owner: owner.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [owner],
+      });
+    });
+
+    it("Transfers the ownership once accepted", async () => {
+      await proposeOwner(newOwner.publicKey, owner);
+
+      let l = await program.account.lottery.fetch(await findLottery());
+      expect(l.owner.toString()).to.equal(owner.publicKey.toString());
+      expect(l.pendingOwner.toString()).to.equal(
+        newOwner.publicKey.toString()
+      );
+
+      await acceptOwnership(newOwner);
+
+      l = await program.account.lottery.fetch(await findLottery());
+      expect(l.owner.toString()).to.equal(newOwner.publicKey.toString());
+      expect(l.pendingOwner.toString()).to.equal(new PublicKey(0).toString());
+    });
+
+    it("Can't be accepted by another wallet", async () => {
+      await proposeOwner(newOwner.publicKey, owner);
+      await assertFail(acceptOwnership(owner));
+    });
+
+    it("Can't be accepted once cancelled", async () => {
+      await proposeOwner(newOwner.publicKey, owner);
+      await program.rpc.cancelOwnerProposal({
+        accounts: {
+          lottery: await findLottery(),
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      await assertFail(acceptOwnership(newOwner));
+    });
+  });
@@ -0,0 +1,161 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import { airdropUsers, assertFail } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+export const testTransferOwnership = (
+  state: {
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Transfer the ownership of the jungle", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    let jungleKey: PublicKey, owner: Keypair, newOwner: Keypair;
+
+    const findJungle = async () =>
+      (
+        await PublicKey.findProgramAddress(
+          [Buffer.from("jungle"), jungleKey.toBuffer()],
+          program.programId
+        )
+      )[0];
+
+    const proposeOwner = async (proposed: PublicKey, signer: Keypair) =>
+      await program.rpc.proposeOwner({
+        accounts: {
+          jungle: await findJungle(),
+          owner: signer.publicKey,
+          newOwner: proposed,
+        },
+        signers: [signer],
+      });
+
+    const acceptOwnership = async (signer: Keypair) =>
+      await program.rpc.acceptOwnership({
+        accounts: {
+          jungle: await findJungle(),
+          pendingOwner: signer.publicKey,
+        },
+        signers: [signer],
+      });
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      newOwner = Keypair.generate();
+      await airdropUsers([owner, newOwner], provider);
+
+      const mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        new BN(10),
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        Array(32).fill(0),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+    });
+
+    it("Transfers the ownership once accepted", async () => {
+      await proposeOwner(newOwner.publicKey, owner);
+
+      let j = await program.account.jungle.fetch(await findJungle());
+      expect(j.owner.toString()).to.equal(owner.publicKey.toString());
+      expect(j.pendingOwner.toString()).to.equal(
+        newOwner.publicKey.toString()
+      );
+
+      await acceptOwnership(newOwner);
+
+      j = await program.account.jungle.fetch(await findJungle());
+      expect(j.owner.toString()).to.equal(newOwner.publicKey.toString());
+      expect(j.pendingOwner.toString()).to.equal(new PublicKey(0).toString());
+
+      // The previous owner lost control
+      await assertFail(proposeOwner(owner.publicKey, owner));
+    });
+
+    it("Can't be accepted by another wallet", async () => {
+      await proposeOwner(newOwner.publicKey, owner);
+      await assertFail(acceptOwnership(owner));
+    });
+
+    it("Can't be proposed by an outsider", async () => {
+      await assertFail(proposeOwner(newOwner.publicKey, newOwner));
+    });
+
+    it("Can't be accepted once cancelled", async () => {
+      await proposeOwner(newOwner.publicKey, owner);
+      await program.rpc.cancelOwnerProposal({
+        accounts: {
+          jungle: await findJungle(),
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+      const j = await program.account.jungle.fetch(await findJungle());
+      expect(j.pendingOwner.toString()).to.equal(new PublicKey(0).toString());
+
+      await assertFail(acceptOwnership(newOwner));
+    });
+  });
@@ -14,7 +14,14 @@ import {
   SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { Jungle } from "../../target/types/jungle";
-import { airdropUsers, assertFail, merkleCollection } from "../helpers";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  lockTiers,
+  faction,
+  findAttributesAddress,
+} from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { MerkleTree } from "../helpers/merkleTree";
 
@@ -108,6 +115,7 @@ export const testUnstakeAnimal = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        new BN(1),
         {
           accounts: {
             jungleKey: jungleKey,
@@ -127,6 +135,7 @@ export const testUnstakeAnimal = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -134,6 +143,7 @@ export const testUnstakeAnimal = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -148,7 +158,8 @@ export const testUnstakeAnimal = (
         bumpsAnimal,
         tree.getProofArray(indexStaked),
         new BN(indexStaked),
-        new BN(indexStaked % 8),
+        faction(indexStaked % 8),
+        0,
         {
           accounts: {
             jungle: jungleAddress,
@@ -157,6 +168,12 @@ export const testUnstakeAnimal = (
             staker: holders[indexStaked].publicKey,
             mint: mints[indexStaked].publicKey,
             stakerAccount: accounts[indexStaked],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[indexStaked].publicKey,
+              program.programId
+            ),
             depositAccount: deposit,
             tokenProgram: TOKEN_PROGRAM_ID,
             clock: SYSVAR_CLOCK_PUBKEY,
@@ -180,6 +197,7 @@ export const testUnstakeAnimal = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -187,6 +205,7 @@ export const testUnstakeAnimal = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -196,9 +215,9 @@ export const testUnstakeAnimal = (
         indexStaked
       ].getOrCreateAssociatedAccountInfo(holders[indexStaked].publicKey);
 
-      const animalsStakedBefore = (
-        await program.account.jungle.fetch(jungleAddress)
-      ).animalsStaked;
+      const jungleBefore = await program.account.jungle.fetch(jungleAddress);
+      const animalsStakedBefore = jungleBefore.animalsStaked;
+      const animalWeight = (await program.account.animal.fetch(animal)).weight;
 
       await program.rpc.unstakeAnimal({
         accounts: {
@@ -209,7 +228,12 @@ export const testUnstakeAnimal = (
           mint: mints[indexStaked].publicKey,
           stakerAccount: stakerAccount.address,
           depositAccount: deposit,
+          edition: SystemProgram.programId,
+          tokenMetadataProgram: SystemProgram.programId,
+          rewardsAccount: SystemProgram.programId,
+          stakerRewardsAccount: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
         },
         signers: [holders[indexStaked]],
       });
@@ -219,6 +243,14 @@ export const testUnstakeAnimal = (
       expect(j.animalsStaked.toString()).to.equal(
         animalsStakedBefore.sub(new BN(1)).toString()
       );
+      expect(j.totalWeight.toString()).to.equal(
+        jungleBefore.totalWeight.sub(animalWeight).toString()
+      );
+      expect(j.factionStats[indexStaked % 8].animals.toString()).to.equal(
+        jungleBefore.factionStats[indexStaked % 8].animals
+          .sub(new BN(1))
+          .toString()
+      );
 
       stakerAccount = await mints[indexStaked].getOrCreateAssociatedAccountInfo(
         holders[indexStaked].publicKey
@@ -238,6 +270,7 @@ export const testUnstakeAnimal = (
       const [animal, animalBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -245,6 +278,7 @@ export const testUnstakeAnimal = (
       const [deposit, depositBump] = await PublicKey.findProgramAddress(
         [
           Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
           mints[indexStaked].publicKey.toBuffer(),
         ],
         program.programId
@@ -269,10 +303,290 @@ export const testUnstakeAnimal = (
             mint: mints[indexStaked].publicKey,
             stakerAccount: stakerAccount.address,
             depositAccount: deposit,
+            edition: SystemProgram.programId,
+            tokenMetadataProgram: SystemProgram.programId,
+            rewardsAccount: SystemProgram.programId,
+            stakerRewardsAccount: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
           },
           signers: [stranger],
         })
       );
     });
+
+    it("Can't unstake a locked token", async () => {
+      const indexLocked = 7;
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexLocked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexLocked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      // Lock for 30 days with a 50% bonus
+      const duration = new BN(30 * 86400);
+      await program.rpc.setJungle(
+        maxRarity,
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        { deposit: {} },
+        lockTiers([
+          [0, 10000],
+          [duration.toNumber(), 15000],
+        ]),
+        {
+          accounts: {
+            jungle: jungleAddress,
+            owner: owner.publicKey,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [owner],
+        }
+      );
+
+      // The animal staked before the new tier doesn't need to be reweighted first
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(indexLocked),
+        new BN(indexLocked),
+        faction(indexLocked % 8),
+        1,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: holders[indexLocked].publicKey,
+            mint: mints[indexLocked].publicKey,
+            stakerAccount: accounts[indexLocked],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[indexLocked].publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [holders[indexLocked]],
+        }
+      );
+
+      const a = await program.account.animal.fetch(animal);
+      const rarityWeight = new BN(10000).add(
+        state.maxMultiplier
+          .sub(new BN(10000))
+          .mul(new BN(indexLocked))
+          .div(maxRarity)
+      );
+      expect(a.lockTier).to.equal(1);
+      expect(a.lockedUntil.sub(a.lastClaim).toString()).to.equal(
+        duration.toString()
+      );
+      expect(a.weight.toString()).to.equal(
+        rarityWeight.mul(new BN(15000)).div(new BN(10000)).toString()
+      );
+
+      await assertFail(
+        program.rpc.unstakeAnimal({
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            animal: animal,
+            staker: holders[indexLocked].publicKey,
+            mint: mints[indexLocked].publicKey,
+            stakerAccount: accounts[indexLocked],
+            depositAccount: deposit,
+            edition: SystemProgram.programId,
+            tokenMetadataProgram: SystemProgram.programId,
+            rewardsAccount: SystemProgram.programId,
+            stakerRewardsAccount: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          signers: [holders[indexLocked]],
+        })
+      );
+    });
+
+    const unstakeParams = async (pay: boolean) => {
+      const holder = holders[indexStaked];
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [animal] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit", "utf8"),
+          jungleKey.toBuffer(),
+          mints[indexStaked].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [rewards] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards", "utf8"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const stakerRewardsAccount = (
+        await mintRewards.getOrCreateAssociatedAccountInfo(holder.publicKey)
+      ).address;
+
+      return {
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          animal: animal,
+          staker: holder.publicKey,
+          mint: mints[indexStaked].publicKey,
+          stakerAccount: accounts[indexStaked],
+          depositAccount: deposit,
+          edition: SystemProgram.programId,
+          tokenMetadataProgram: SystemProgram.programId,
+          rewardsAccount: pay ? rewards : SystemProgram.programId,
+          stakerRewardsAccount: pay
+            ? stakerRewardsAccount
+            : SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        signers: [holder],
+      };
+    };
+
+    it("Pays the pending rewards when unstaking", async () => {
+      const params = await unstakeParams(true);
+      await mintRewards.mintTo(
+        params.accounts.rewardsAccount,
+        owner,
+        [],
+        10 ** 14
+      );
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await program.rpc.unstakeAnimal(params);
+
+      const rewards = await mintRewards.getAccountInfo(
+        params.accounts.stakerRewardsAccount
+      );
+      const j = await program.account.jungle.fetch(params.accounts.jungle);
+      expect(rewards.amount.toNumber()).to.be.above(0);
+      expect(j.animalsStaked.toString()).to.equal("0");
+    });
+
+    it("Can't forfeit pending rewards under the reject policy", async () => {
+      const params = await unstakeParams(false);
+      await program.rpc.setUnstakePolicy(
+        { reject: {} },
+        {
+          accounts: {
+            jungle: params.accounts.jungle,
+            owner: owner.publicKey,
+          },
+          signers: [owner],
+        }
+      );
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await assertFail(program.rpc.unstakeAnimal(params));
+
+      // Paying the rewards is accepted
+      await mintRewards.mintTo(
+        (await unstakeParams(true)).accounts.rewardsAccount,
+        owner,
+        [],
+        10 ** 14
+      );
+      await program.rpc.unstakeAnimal(await unstakeParams(true));
+    });
+
+    it("Unstakes under the reject policy while claims are paused", async () => {
+      const params = await unstakeParams(false);
+      await program.rpc.setUnstakePolicy(
+        { reject: {} },
+        {
+          accounts: {
+            jungle: params.accounts.jungle,
+            owner: owner.publicKey,
+          },
+          signers: [owner],
+        }
+      );
+      await program.rpc.setPause(2, {
+        accounts: {
+          jungle: params.accounts.jungle,
+          authority: owner.publicKey,
+        },
+        signers: [owner],
+      });
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      // The rewards can't be paid, so they are forfeited rather than keeping the token
+      await program.rpc.unstakeAnimal(params);
+
+      const j = await program.account.jungle.fetch(params.accounts.jungle);
+      expect(j.animalsStaked.toString()).to.equal("0");
+    });
+
+    it("Unstakes under the reject policy when the vault is short", async () => {
+      const params = await unstakeParams(true);
+      await program.rpc.setUnstakePolicy(
+        { reject: {} },
+        {
+          accounts: {
+            jungle: params.accounts.jungle,
+            owner: owner.publicKey,
+          },
+          signers: [owner],
+        }
+      );
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      // The vault is empty, the rewards are forfeited
+      await program.rpc.unstakeAnimal(params);
+
+      const rewards = await mintRewards.getAccountInfo(
+        params.accounts.stakerRewardsAccount
+      );
+      expect(rewards.amount.toNumber()).to.equal(0);
+    });
   });
@@ -0,0 +1,281 @@
+import { expect } from "chai";
+import {
+  setProvider,
+  Provider,
+  Program,
+  workspace,
+  BN,
+} from "@project-serum/anchor";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import { Jungle } from "../../target/types/jungle";
+import { airdropUsers, assertFail, merkleCollection, faction } from "../helpers";
+import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { MerkleTree } from "../helpers/merkleTree";
+
+export const testUnstakeAnimals = (
+  state: {
+    owner: Keypair;
+    staker: Keypair;
+    jungleKey: PublicKey;
+    mintRewards: Token;
+    maxMultiplier: BN;
+    baseWeeklyEmissions: BN;
+    start: BN;
+  },
+  provider: Provider
+) =>
+  describe("Unstake several animals", () => {
+    setProvider(provider);
+
+    const program = workspace.Jungle as Program<Jungle>;
+
+    const n = 10;
+    let mintRewards: Token,
+      mints: Token[],
+      accounts: PublicKey[] = Array(n).fill(new PublicKey(0));
+    let tree: MerkleTree;
+    let jungleKey: PublicKey, owner: Keypair, staker: Keypair, stranger: Keypair;
+
+    const maxRarity = new BN(n);
+    const indexesStaked = [1, 4, 5, 8];
+
+    // The leaves and remaining accounts of the animals to stake
+    const animalsParams = async (indexes: number[], staking = false) => {
+      const leaves = [];
+      const remainingAccounts = [];
+      for (const index of indexes) {
+        const [animal, animalBump] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("animal", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const [deposit, depositBump] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("deposit", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const [attributes] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("attributes", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        leaves.push({
+          bumps: { animal: animalBump, deposit: depositBump },
+          index: index,
+          rarity: new BN(index),
+          faction: faction(index % 8),
+          lockTier: 0,
+        });
+        remainingAccounts.push(
+          { pubkey: animal, isWritable: true, isSigner: false },
+          {
+            pubkey: mints[index].publicKey,
+            isWritable: false,
+            isSigner: false,
+          },
+          { pubkey: accounts[index], isWritable: true, isSigner: false },
+          { pubkey: deposit, isWritable: true, isSigner: false }
+        );
+        // Staking also reads the attributes PDA of each mint
+        if (staking) {
+          remainingAccounts.push({
+            pubkey: attributes,
+            isWritable: false,
+            isSigner: false,
+          });
+        }
+      }
+      return { leaves, remainingAccounts };
+    };
+
+    beforeEach(async () => {
+      jungleKey = Keypair.generate().publicKey;
+      owner = Keypair.generate();
+      staker = Keypair.generate();
+      stranger = Keypair.generate();
+      await airdropUsers([owner, staker, stranger], provider);
+
+      mintRewards = await Token.createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9,
+        TOKEN_PROGRAM_ID
+      );
+      const nfts = await merkleCollection(owner, n, provider);
+      mints = nfts.mints;
+      await Promise.all(
+        mints.map(async (mint, i) => {
+          accounts[i] = (
+            await mint.getOrCreateAssociatedAccountInfo(staker.publicKey)
+          ).address;
+          const ownerAccount = (
+            await mint.getOrCreateAssociatedAccountInfo(owner.publicKey)
+          ).address;
+          await mint.transfer(ownerAccount, accounts[i], owner, [], 1);
+        })
+      );
+      tree = nfts.tree;
+
+      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.rpc.initializeJungle(
+        { jungle: jungleBump, escrow: escrowBump, rewards: rewardsBump },
+        maxRarity,
+        state.maxMultiplier,
+        state.baseWeeklyEmissions,
+        state.start,
+        tree.getRootArray(),
+        new BN(1),
+        {
+          accounts: {
+            jungleKey: jungleKey,
+            jungle: jungleAddress,
+            escrow: escrow,
+            mint: mintRewards.publicKey,
+            rewardsAccount: rewards,
+            owner: owner.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [owner],
+        }
+      );
+
+      const { leaves, remainingAccounts } = await animalsParams(indexesStaked, true);
+      await program.rpc.stakeAnimals(
+        n,
+        tree.getMultiProofArray(indexesStaked),
+        leaves,
+        {
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            staker: staker.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          remainingAccounts,
+          signers: [staker],
+        }
+      );
+    });
+
+    it("Unstakes several animals", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const { remainingAccounts } = await animalsParams(indexesStaked);
+
+      await program.rpc.unstakeAnimals({
+        accounts: {
+          jungle: jungleAddress,
+          escrow: escrow,
+          staker: staker.publicKey,
+          rewardsAccount: SystemProgram.programId,
+          stakerRewardsAccount: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        remainingAccounts,
+        signers: [staker],
+      });
+
+      const j = await program.account.jungle.fetch(jungleAddress);
+      expect(j.animalsStaked.toString()).to.equal("0");
+      expect(j.totalWeight.toString()).to.equal("0");
+
+      for (const index of indexesStaked) {
+        const [animal] = await PublicKey.findProgramAddress(
+          [
+            Buffer.from("animal", "utf8"),
+            jungleKey.toBuffer(),
+            mints[index].publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        expect(await provider.connection.getAccountInfo(animal)).to.equal(
+          null
+        );
+
+        const stakerAccount = await mints[index].getAccountInfo(
+          accounts[index]
+        );
+        expect(stakerAccount.amount.toString()).to.equal("1");
+      }
+    });
+
+    it("Can't unstake animals of another staker", async () => {
+      const [jungleAddress] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const { remainingAccounts } = await animalsParams(indexesStaked);
+      for (let i = 0; i < indexesStaked.length; i++) {
+        remainingAccounts[4 * i + 2].pubkey = (
+          await mints[indexesStaked[i]].getOrCreateAssociatedAccountInfo(
+            stranger.publicKey
+          )
+        ).address;
+      }
+
+      await assertFail(
+        program.rpc.unstakeAnimals({
+          accounts: {
+            jungle: jungleAddress,
+            escrow: escrow,
+            staker: stranger.publicKey,
+            rewardsAccount: SystemProgram.programId,
+            stakerRewardsAccount: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+          },
+          remainingAccounts,
+          signers: [stranger],
+        })
+      );
+    });
+  });
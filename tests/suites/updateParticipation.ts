@@ -13,9 +13,10 @@ import {
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
   SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
 } from "@solana/web3.js";
 import { Lottery } from "../../target/types/lottery";
-import { airdropUsers, assertFail } from "../helpers";
+import { airdropUsers, assertFail, generateSecret } from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 export const testUpdateParticipation = (provider: Provider) =>
@@ -29,6 +30,7 @@ export const testUpdateParticipation = (provider: Provider) =>
     let lotteryKey: PublicKey, treasury: PublicKey, playerAccount: PublicKey;
 
     const startingAmount = new BN(10 ** 10);
+    const revealWindow = new BN(100);
     const period = new BN(5);
 
     beforeEach(async () => {
@@ -83,7 +85,7 @@ export const testUpdateParticipation = (provider: Provider) =>
 
       const start = Math.round(Date.now() / 1000) - 6;
 
-      await program.rpc.initializeLottery(bumps, period, new BN(start), {
+      await program.rpc.initializeLottery(bumps, period, new BN(start), revealWindow, {
         accounts: {
           lotteryKey: lotteryKey,
           lottery: lotteryAddress,
@@ -121,19 +123,31 @@ export const testUpdateParticipation = (provider: Provider) =>
         [owner]
       );
 
-      await program.rpc.newLotteryRound(nextRoundBump, {
+      await program.rpc.newLotteryRound(nextRoundBump, null, {
         accounts: {
           lottery: lotteryAddress,
           escrow: escrow,
           lotteryRound: nextRound,
           oldLotteryRound: round,
+          randomness: SystemProgram.programId,
           payer: player.publicKey,
+          slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
           clock: SYSVAR_CLOCK_PUBKEY,
           rent: SYSVAR_RENT_PUBKEY,
           systemProgram: SystemProgram.programId,
         },
         signers: [player],
       });
+
+      // Players can only enter the round once its secret is committed
+      await program.rpc.commitLotteryRound(generateSecret().commitment, {
+        accounts: {
+          lottery: lotteryAddress,
+          lotteryRound: nextRound,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
     });
 
     it("Update a participation", async () => {
@@ -11,9 +11,15 @@ import {
   Keypair,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
 } from "@solana/web3.js";
 import { Jungle } from "../../target/types/jungle";
-import { airdropUsers, assertFail, merkleCollection } from "../helpers";
+import {
+  airdropUsers,
+  assertFail,
+  merkleCollection,
+  findAttributesAddress,
+} from "../helpers";
 import { Token, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { MerkleTree } from "../helpers/merkleTree";
 
@@ -44,6 +50,92 @@ export const testWithdrawRewards = (
 
     const startingAmount = new BN(10 ** 10);
 
+    const findAddresses = async (index: number) => {
+      const [jungle] = await PublicKey.findProgramAddress(
+        [Buffer.from("jungle"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [escrow] = await PublicKey.findProgramAddress(
+        [Buffer.from("escrow"), jungleKey.toBuffer()],
+        program.programId
+      );
+      const [rewards] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("rewards"),
+          jungleKey.toBuffer(),
+          mintRewards.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [withdrawal, withdrawalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("withdrawal"),
+          jungleKey.toBuffer(),
+          new BN(index).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      return { jungle, escrow, rewards, withdrawal, withdrawalBump };
+    };
+
+    const setWithdrawalLimits = async (delay: number, cap: number) =>
+      await program.rpc.setWithdrawalLimits(new BN(delay), new BN(cap), {
+        accounts: {
+          jungle: (await findAddresses(0)).jungle,
+          owner: owner.publicKey,
+        },
+        signers: [owner],
+      });
+
+    const queueWithdrawal = async (
+      index: number,
+      amount: BN,
+      destination: PublicKey
+    ) => {
+      const a = await findAddresses(index);
+      await program.rpc.queueWithdrawal(a.withdrawalBump, amount, {
+        accounts: {
+          jungle: a.jungle,
+          withdrawal: a.withdrawal,
+          owner: owner.publicKey,
+          destination: destination,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [owner],
+      });
+    };
+
+    const cancelWithdrawal = async (index: number, signer: Keypair) => {
+      const a = await findAddresses(index);
+      await program.rpc.cancelWithdrawal({
+        accounts: {
+          jungle: a.jungle,
+          withdrawal: a.withdrawal,
+          owner: signer.publicKey,
+        },
+        signers: [signer],
+      });
+    };
+
+    const executeWithdrawal = async (index: number, destination: PublicKey) => {
+      const a = await findAddresses(index);
+      await program.rpc.executeWithdrawal({
+        accounts: {
+          jungle: a.jungle,
+          escrow: a.escrow,
+          rewardsAccount: a.rewards,
+          withdrawal: a.withdrawal,
+          owner: owner.publicKey,
+          destination: destination,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        signers: [owner],
+      });
+    };
+
     beforeEach(async () => {
       jungleKey = Keypair.generate().publicKey;
       owner = Keypair.generate();
@@ -106,6 +198,7 @@ export const testWithdrawRewards = (
         state.baseWeeklyEmissions,
         state.start,
         tree.getRootArray(),
+        new BN(1),
         {
           accounts: {
             jungleKey: jungleKey,
@@ -127,41 +220,16 @@ export const testWithdrawRewards = (
     });
 
     it("Withdraw rewards", async () => {
-      const [jungleAddress, jungleBump] = await PublicKey.findProgramAddress(
-        [Buffer.from("jungle"), jungleKey.toBuffer()],
-        program.programId
-      );
-      const [escrow, escrowBump] = await PublicKey.findProgramAddress(
-        [Buffer.from("escrow", "utf8"), jungleKey.toBuffer()],
-        program.programId
-      );
-      const [rewards, rewardsBump] = await PublicKey.findProgramAddress(
-        [
-          Buffer.from("rewards"),
-          jungleKey.toBuffer(),
-          mintRewards.publicKey.toBuffer(),
-        ],
-        program.programId
-      );
+      const { rewards } = await findAddresses(0);
 
       let ownerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
         owner.publicKey
       );
-
       const withdraw = new BN(10 ** 9);
 
-      await program.rpc.withdrawRewards(withdraw, {
-        accounts: {
-          jungle: jungleAddress,
-          escrow: escrow,
-          mint: mintRewards.publicKey,
-          rewardsAccount: rewards,
-          owner: owner.publicKey,
-          ownerAccount: ownerAccount.address,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        },
-        signers: [owner],
-      });
+      await queueWithdrawal(0, withdraw, ownerAccount.address);
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await executeWithdrawal(0, ownerAccount.address);
 
       expect(
         (
@@ -171,5 +239,172 @@ export const testWithdrawRewards = (
       expect(
         (await mintRewards.getAccountInfo(rewards)).amount.toNumber()
       ).to.equal(startingAmount.sub(withdraw).toNumber());
+
+      // The withdrawal can only be executed once
+      await assertFail(executeWithdrawal(0, ownerAccount.address));
+    });
+
+    it("Can't withdraw before the delay", async () => {
+      const ownerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        owner.publicKey
+      );
+      await setWithdrawalLimits(2, 0);
+
+      await queueWithdrawal(0, new BN(10 ** 9), ownerAccount.address);
+      await assertFail(executeWithdrawal(0, ownerAccount.address));
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await executeWithdrawal(0, ownerAccount.address);
+    });
+
+    it("Applies the delay raised after queuing", async () => {
+      const ownerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        owner.publicKey
+      );
+
+      await queueWithdrawal(0, new BN(10 ** 9), ownerAccount.address);
+      await setWithdrawalLimits(4, 0);
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await assertFail(executeWithdrawal(0, ownerAccount.address));
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await executeWithdrawal(0, ownerAccount.address);
+    });
+
+    it("Cancel a queued withdrawal", async () => {
+      const { withdrawal } = await findAddresses(0);
+      const ownerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        owner.publicKey
+      );
+
+      await queueWithdrawal(0, new BN(10 ** 9), ownerAccount.address);
+      await assertFail(cancelWithdrawal(0, stranger));
+      await cancelWithdrawal(0, owner);
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await assertFail(executeWithdrawal(0, ownerAccount.address));
+      expect(await provider.connection.getAccountInfo(withdrawal)).to.be.null;
+    });
+
+    it("Can't withdraw more than the weekly cap", async () => {
+      const ownerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        owner.publicKey
+      );
+      await setWithdrawalLimits(1, 10 ** 9);
+
+      await assertFail(
+        queueWithdrawal(0, new BN(10 ** 9 + 1), ownerAccount.address)
+      );
+
+      await queueWithdrawal(0, new BN(6 * 10 ** 8), ownerAccount.address);
+      await queueWithdrawal(1, new BN(6 * 10 ** 8), ownerAccount.address);
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await executeWithdrawal(0, ownerAccount.address);
+      await assertFail(executeWithdrawal(1, ownerAccount.address));
+    });
+
+    it("Can only tighten the limits", async () => {
+      await setWithdrawalLimits(100, 10 ** 9);
+
+      await assertFail(setWithdrawalLimits(50, 10 ** 9));
+      await assertFail(setWithdrawalLimits(100, 10 ** 10));
+      await assertFail(setWithdrawalLimits(100, 0));
+
+      await setWithdrawalLimits(200, 10 ** 8);
+      const j = await program.account.jungle.fetch(
+        (await findAddresses(0)).jungle
+      );
+      expect(j.withdrawalDelay.toNumber()).to.equal(200);
+      expect(j.withdrawalCap.toNumber()).to.equal(10 ** 8);
+    });
+
+    it("Can't withdraw the rewards owed to stakers", async () => {
+      const a = await findAddresses(0);
+      const index = 3;
+      const [animal, animalBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("animal"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [deposit, depositBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("deposit"),
+          jungleKey.toBuffer(),
+          mints[index].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      await program.rpc.stakeAnimal(
+        { animal: animalBump, deposit: depositBump },
+        tree.getProofArray(index),
+        new BN(index),
+        new BN(0),
+        0,
+        {
+          accounts: {
+            jungle: a.jungle,
+            escrow: a.escrow,
+            animal: animal,
+            staker: holders[index].publicKey,
+            mint: mints[index].publicKey,
+            stakerAccount: accounts[index],
+            metadata: SystemProgram.programId,
+            attributes: await findAttributesAddress(
+              jungleKey,
+              mints[index].publicKey,
+              program.programId
+            ),
+            depositAccount: deposit,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [holders[index]],
+        }
+      );
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const ownerAccount = await mintRewards.getOrCreateAssociatedAccountInfo(
+        owner.publicKey
+      );
+      await queueWithdrawal(0, startingAmount, ownerAccount.address);
+      await queueWithdrawal(1, new BN(10 ** 9), ownerAccount.address);
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await assertFail(executeWithdrawal(0, ownerAccount.address));
+      await executeWithdrawal(1, ownerAccount.address);
+
+      const j = await program.account.jungle.fetch(a.jungle);
+      expect(j.liabilities.toNumber()).to.be.above(0);
+      expect(
+        (await mintRewards.getAccountInfo(a.rewards)).amount.toNumber()
+      ).to.be.at.least(j.liabilities.toNumber());
+    });
+
+    it("Fails when not called by the owner", async () => {
+      const a = await findAddresses(0);
+      const strangerAccount =
+        await mintRewards.getOrCreateAssociatedAccountInfo(stranger.publicKey);
+
+      await assertFail(
+        program.rpc.queueWithdrawal(a.withdrawalBump, new BN(1), {
+          accounts: {
+            jungle: a.jungle,
+            withdrawal: a.withdrawal,
+            owner: stranger.publicKey,
+            destination: strangerAccount.address,
+            clock: SYSVAR_CLOCK_PUBKEY,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [stranger],
+        })
+      );
     });
   });